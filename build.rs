@@ -0,0 +1,59 @@
+//! Collects the solutions in `src/bin` into the `solutions` binary.
+//! Each solution file is included as a module and its `SOLUTION` static is added to the registry.
+use std::{env, fs, path::Path};
+
+fn is_solution_file(name: &str) -> bool {
+    // solution binaries are named `<year>-<day>.rs`, e.g. `2024-01.rs`.
+    let Some(stem) = name.strip_suffix(".rs") else {
+        return false;
+    };
+    let Some((year, day)) = stem.split_once('-') else {
+        return false;
+    };
+    year.len() == 4
+        && day.len() == 2
+        && year.chars().all(|c| c.is_ascii_digit())
+        && day.chars().all(|c| c.is_ascii_digit())
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=src/bin");
+
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let bin_dir = Path::new(&manifest_dir).join("src").join("bin");
+
+    let mut names: Vec<String> = fs::read_dir(&bin_dir)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .filter(|name| is_solution_file(name))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    names.sort();
+
+    let mut modules = String::new();
+    let mut entries = String::new();
+
+    for name in &names {
+        let module = format!(
+            "solution_{}",
+            name.trim_end_matches(".rs").replace('-', "_")
+        );
+        let path = bin_dir.join(name);
+        modules.push_str(&format!(
+            "#[path = {:?}]\n#[allow(dead_code, unused_imports)]\nmod {module};\n",
+            path.display().to_string()
+        ));
+        entries.push_str(&format!("    &{module}::SOLUTION,\n"));
+    }
+
+    let registry = format!(
+        "{modules}\npub static SOLUTIONS: &[&advent_of_code::template::registry::Solution] = &[\n{entries}];\n"
+    );
+
+    let out_dir = env::var("OUT_DIR").unwrap();
+    fs::write(Path::new(&out_dir).join("solutions.rs"), registry).unwrap();
+}
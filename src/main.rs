@@ -1,79 +1,298 @@
-use advent_of_code::template::commands::{all, download, read, scaffold, solve, time};
+use advent_of_code::template::commands::{
+    all, answer, dhat, download, examples, read, scaffold, solve, time, watch,
+};
 use args::{parse, AppArguments};
 
 #[cfg(feature = "today")]
-use advent_of_code::template::Day;
+use advent_of_code::template::PuzzleId;
 #[cfg(feature = "today")]
 use std::process;
 
 mod args {
-    use advent_of_code::template::Day;
-    use std::process;
+    use advent_of_code::template::{
+        bench::BenchConfig,
+        commands::{dhat, examples},
+        config::config,
+        limits::Limits,
+        paths,
+        test_report::ReportTarget,
+        Day, PuzzleId, Year,
+    };
+    use std::{env, ffi::OsString, path::Path, process, time::Duration};
 
     pub enum AppArguments {
         Download {
-            day: Day,
+            puzzle: PuzzleId,
         },
         Read {
-            day: Day,
+            puzzle: PuzzleId,
+        },
+        Examples {
+            puzzle: PuzzleId,
+            write: Option<Vec<usize>>,
+            overwrite: bool,
         },
         Scaffold {
-            day: Day,
+            puzzle: PuzzleId,
             download: bool,
             overwrite: bool,
+            template: Option<String>,
+            answer_type: Option<String>,
         },
         Solve {
-            day: Day,
+            puzzle: PuzzleId,
             release: bool,
             dhat: bool,
             submit: Option<u8>,
+            watch: bool,
+            input: Option<String>,
         },
         All {
+            year: Year,
             release: bool,
+            isolated: bool,
+            jobs: usize,
+            limits: Limits,
+            report: Option<ReportTarget>,
         },
         Time {
             all: bool,
+            year: Year,
             day: Option<Day>,
             store: bool,
+            isolated: bool,
+            bench: BenchConfig,
+            jobs: usize,
+            /// Regression threshold in percent, if timings should be compared against the benchmark history.
+            compare: Option<f64>,
+            limits: Limits,
+            report: Option<ReportTarget>,
+        },
+        Answer {
+            puzzle: PuzzleId,
+            part: u8,
+            value: String,
+        },
+        Dhat {
+            profile: Option<String>,
+            other: Option<String>,
+            top: usize,
         },
         #[cfg(feature = "today")]
         Today,
     }
 
+    /// Resolves the event year from the `--year` option, falling back to the year in `aoc.toml` and then to the
+    /// `AOC_YEAR` environment variable.
+    fn parse_year(args: &mut pico_args::Arguments) -> Result<Year, Box<dyn std::error::Error>> {
+        match args.opt_value_from_str("--year")? {
+            Some(year) => Ok(year),
+            None => config().year.or_else(Year::from_env).ok_or_else(|| {
+                "no year specified. Pass `--year <year>` or set `year` in `aoc.toml`.".into()
+            }),
+        }
+    }
+
+    /// Parses the number of days run at the same time, defaulting to one.
+    fn parse_jobs(args: &mut pico_args::Arguments) -> Result<usize, Box<dyn std::error::Error>> {
+        match args.opt_value_from_str::<_, usize>("--jobs")? {
+            Some(0) => Err("`--jobs` needs to be at least 1.".into()),
+            Some(jobs) => Ok(jobs),
+            None => Ok(1),
+        }
+    }
+
+    /// Resolves the file passed to a solution from the `--input <path>` and `--example [n]` options.
+    /// Defaults to the puzzle input if neither is present.
+    fn parse_input(
+        args: &mut pico_args::Arguments,
+        puzzle: PuzzleId,
+    ) -> Result<Option<String>, Box<dyn std::error::Error>> {
+        let path: Option<String> = args.opt_value_from_str("--input")?;
+
+        // `--example` takes an optional value, so it is parsed after all other options.
+        let example = if args.contains("--example") {
+            Some(match args.opt_free_from_str::<u8>()? {
+                Some(part) => paths::data_file_part("examples", puzzle, part),
+                None => paths::example(puzzle),
+            })
+        } else {
+            None
+        };
+
+        let input = match (path, example) {
+            (Some(_), Some(_)) => {
+                return Err("`--input` can not be combined with `--example`.".into())
+            }
+            (path, example) => path.or(example),
+        };
+
+        match input {
+            Some(input) if !Path::new(&input).exists() => {
+                Err(format!("could not find input file {input}.").into())
+            }
+            input => Ok(input),
+        }
+    }
+
+    /// Parses the limits of each day from the `--timeout <secs>` and `--memory-limit <MiB>` options, falling back to
+    /// the environment and `aoc.toml`. A value of `0` disables a limit.
+    fn parse_limits(args: &mut pico_args::Arguments) -> Result<Limits, Box<dyn std::error::Error>> {
+        let default = Limits::from_env();
+
+        Ok(Limits::new(
+            args.opt_value_from_str("--timeout")?
+                .unwrap_or(default.timeout_secs()),
+            args.opt_value_from_str("--memory-limit")?
+                .unwrap_or(default.memory_mib()),
+        ))
+    }
+
+    fn parse_puzzle(
+        args: &mut pico_args::Arguments,
+        year: Year,
+    ) -> Result<PuzzleId, Box<dyn std::error::Error>> {
+        Ok(PuzzleId::new(year, args.free_from_str()?))
+    }
+
     pub fn parse() -> Result<AppArguments, Box<dyn std::error::Error>> {
-        let mut args = pico_args::Arguments::from_env();
+        let mut raw_args: Vec<OsString> = env::args_os().skip(1).collect();
+        let mut report = ReportTarget::take_from_args(&mut raw_args)?;
+        let mut args = pico_args::Arguments::from_vec(raw_args);
 
         let app_args = match args.subcommand()?.as_deref() {
             Some("all") => AppArguments::All {
+                year: parse_year(&mut args)?,
                 release: args.contains("--release"),
+                isolated: args.contains("--isolated"),
+                jobs: parse_jobs(&mut args)?,
+                limits: parse_limits(&mut args)?,
+                report: report.take(),
             },
             Some("time") => {
                 let all = args.contains("--all");
                 let store = args.contains("--store");
+                let isolated = args.contains("--isolated");
+                let compare = args.contains("--compare");
+                let threshold: Option<f64> = args.opt_value_from_str("--threshold")?;
+                let allow_parallel = args.contains("--allow-parallel");
+                let year = parse_year(&mut args)?;
+                let limits = parse_limits(&mut args)?;
+
+                // parallel benchmarks compete for CPU time, which skews their timings.
+                let mut jobs = parse_jobs(&mut args)?;
+                if jobs > 1 && !allow_parallel {
+                    eprintln!("Warning: benchmarks run one day at a time. Pass `--allow-parallel` to use `--jobs`.");
+                    jobs = 1;
+                }
+
+                let default = BenchConfig::from_env();
+                let bench = BenchConfig {
+                    budget: args
+                        .opt_value_from_str("--budget")?
+                        .map_or(default.budget, Duration::from_millis),
+                    max_samples: args
+                        .opt_value_from_str("--max-samples")?
+                        .unwrap_or(default.max_samples),
+                    warmup: args
+                        .opt_value_from_str("--warmup")?
+                        .map_or(default.warmup, Duration::from_millis),
+                };
+
+                if threshold.is_some() && !compare {
+                    return Err("`--threshold` can only be used with `--compare`.".into());
+                }
 
                 AppArguments::Time {
                     all,
+                    year,
                     day: args.opt_free_from_str()?,
                     store,
+                    isolated,
+                    bench,
+                    jobs,
+                    compare: compare
+                        .then(|| threshold.unwrap_or(config().history.regression_threshold)),
+                    limits,
+                    report: report.take(),
+                }
+            }
+            Some("answer") => {
+                let year = parse_year(&mut args)?;
+                AppArguments::Answer {
+                    puzzle: parse_puzzle(&mut args, year)?,
+                    part: args.free_from_str()?,
+                    value: args.free_from_str()?,
+                }
+            }
+            Some("dhat") => {
+                let top = args
+                    .opt_value_from_str("--top")?
+                    .unwrap_or(dhat::DEFAULT_TOP);
+                AppArguments::Dhat {
+                    profile: args.opt_free_from_str()?,
+                    other: args.opt_free_from_str()?,
+                    top,
+                }
+            }
+            Some("download") => {
+                let year = parse_year(&mut args)?;
+                AppArguments::Download {
+                    puzzle: parse_puzzle(&mut args, year)?,
+                }
+            }
+            Some("read") => {
+                let year = parse_year(&mut args)?;
+                AppArguments::Read {
+                    puzzle: parse_puzzle(&mut args, year)?,
+                }
+            }
+            Some("examples") => {
+                let year = parse_year(&mut args)?;
+                AppArguments::Examples {
+                    puzzle: parse_puzzle(&mut args, year)?,
+                    write: args.opt_value_from_fn("--write", examples::parse_selection)?,
+                    overwrite: args.contains("--overwrite"),
+                }
+            }
+            Some("scaffold") => {
+                let year = parse_year(&mut args)?;
+                AppArguments::Scaffold {
+                    puzzle: parse_puzzle(&mut args, year)?,
+                    download: args.contains("--download"),
+                    overwrite: args.contains("--overwrite"),
+                    template: args.opt_value_from_str("--template")?,
+                    answer_type: args.opt_value_from_str("--answer-type")?,
+                }
+            }
+            Some("solve") => {
+                let year = parse_year(&mut args)?;
+                let puzzle = parse_puzzle(&mut args, year)?;
+                let release = args.contains("--release");
+                let submit = args.opt_value_from_str("--submit")?;
+                let dhat = args.contains("--dhat");
+                let watch = args.contains("--watch");
+                let input = parse_input(&mut args, puzzle)?;
+
+                if watch && (submit.is_some() || dhat) {
+                    return Err("`--watch` can not be combined with `--submit` or `--dhat`.".into());
+                }
+
+                if input.is_some() && submit.is_some() {
+                    return Err(
+                        "`--submit` can not be combined with `--input` or `--example`.".into(),
+                    );
+                }
+
+                AppArguments::Solve {
+                    puzzle,
+                    release,
+                    submit,
+                    dhat,
+                    watch,
+                    input,
                 }
             }
-            Some("download") => AppArguments::Download {
-                day: args.free_from_str()?,
-            },
-            Some("read") => AppArguments::Read {
-                day: args.free_from_str()?,
-            },
-            Some("scaffold") => AppArguments::Scaffold {
-                day: args.free_from_str()?,
-                download: args.contains("--download"),
-                overwrite: args.contains("--overwrite"),
-            },
-            Some("solve") => AppArguments::Solve {
-                day: args.free_from_str()?,
-                release: args.contains("--release"),
-                submit: args.opt_value_from_str("--submit")?,
-                dhat: args.contains("--dhat"),
-            },
             #[cfg(feature = "today")]
             Some("today") => AppArguments::Today,
             Some(x) => {
@@ -86,6 +305,10 @@ mod args {
             }
         };
 
+        if report.is_some() {
+            return Err("`--report` is only supported by `all` and `time`.".into());
+        }
+
         let remaining = args.finish();
         if !remaining.is_empty() {
             eprintln!("Warning: unknown argument(s): {remaining:?}.");
@@ -102,33 +325,103 @@ fn main() {
             std::process::exit(1);
         }
         Ok(args) => match args {
-            AppArguments::All { release } => all::handle(release),
-            AppArguments::Time { day, all, store } => time::handle(day, all, store),
-            AppArguments::Download { day } => download::handle(day),
-            AppArguments::Read { day } => read::handle(day),
-            AppArguments::Scaffold {
+            AppArguments::All {
+                year,
+                release,
+                isolated,
+                jobs,
+                limits,
+                report,
+            } => all::handle(year, release, isolated, jobs, &limits, report.as_ref()),
+            AppArguments::Time {
+                year,
                 day,
+                all,
+                store,
+                isolated,
+                bench,
+                jobs,
+                compare,
+                limits,
+                report,
+            } => time::handle(
+                year,
+                day,
+                all,
+                store,
+                isolated,
+                &bench,
+                jobs,
+                compare,
+                &limits,
+                report.as_ref(),
+            ),
+            AppArguments::Answer {
+                puzzle,
+                part,
+                value,
+            } => answer::handle(puzzle, part, &value),
+            AppArguments::Dhat {
+                profile,
+                other,
+                top,
+            } => dhat::handle(profile.as_deref(), other.as_deref(), top),
+            AppArguments::Download { puzzle } => download::handle(puzzle),
+            AppArguments::Read { puzzle } => read::handle(puzzle),
+            AppArguments::Examples {
+                puzzle,
+                write,
+                overwrite,
+            } => examples::handle(puzzle, write, overwrite),
+            AppArguments::Scaffold {
+                puzzle,
                 download,
                 overwrite,
+                template,
+                answer_type,
             } => {
-                scaffold::handle(day, overwrite);
+                // download first, so the template can use the puzzle title. a failed download still scaffolds the
+                // solution, just without the title.
                 if download {
-                    download::handle(day);
+                    if let Err(e) = download::try_download(puzzle) {
+                        eprintln!("{e}");
+                        eprintln!("Scaffolding without the puzzle title.");
+                    }
                 }
+                scaffold::handle(
+                    puzzle,
+                    overwrite,
+                    template.as_deref(),
+                    answer_type.as_deref(),
+                );
             }
             AppArguments::Solve {
-                day,
+                puzzle,
                 release,
                 dhat,
                 submit,
-            } => solve::handle(day, release, dhat, submit),
+                watch,
+                input,
+            } => {
+                if watch {
+                    watch::handle(puzzle, release, input.as_deref());
+                } else {
+                    solve::handle(puzzle, release, dhat, submit, input.as_deref());
+                }
+            }
             #[cfg(feature = "today")]
             AppArguments::Today => {
-                match Day::today() {
-                    Some(day) => {
-                        scaffold::handle(day, false);
-                        download::handle(day);
-                        read::handle(day)
+                match PuzzleId::today() {
+                    Some(puzzle) => {
+                        let downloaded = download::try_download(puzzle);
+                        if let Err(e) = &downloaded {
+                            eprintln!("{e}");
+                            eprintln!("Scaffolding without the puzzle title.");
+                        }
+                        scaffold::handle(puzzle, false, None, None);
+                        if downloaded.is_ok() {
+                            read::handle(puzzle)
+                        }
                     }
                     None => {
                         eprintln!(
@@ -0,0 +1,6 @@
+// Runs all solutions of the repository in a single process. Invoked by `cargo all` and `cargo time`.
+include!(concat!(env!("OUT_DIR"), "/solutions.rs"));
+
+fn main() {
+    advent_of_code::template::registry::run(SOLUTIONS);
+}
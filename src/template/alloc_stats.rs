@@ -0,0 +1,221 @@
+/// Counts the heap allocations of solution parts.
+///
+/// The counting allocator is installed by the `alloc-stats` feature. It wraps the system allocator and keeps global
+/// counters, so allocations made by other threads of the same process, e.g. threads spawned by a solution, are
+/// counted as well. Days that run in parallel each run in their own process and do not affect each other's numbers.
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    collections::HashMap,
+    sync::atomic::{AtomicU64, Ordering},
+};
+use tinyjson::JsonValue;
+
+/// Heap allocations made while running a part.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AllocStats {
+    /// Peak heap usage, relative to the heap usage before the part ran.
+    pub peak_bytes: u64,
+    pub allocations: u64,
+    pub allocated_bytes: u64,
+}
+
+/// Global allocator that counts allocations, see [`measure`].
+pub struct CountingAlloc;
+
+static CURRENT_BYTES: AtomicU64 = AtomicU64::new(0);
+static PEAK_BYTES: AtomicU64 = AtomicU64::new(0);
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static ALLOCATED_BYTES: AtomicU64 = AtomicU64::new(0);
+
+fn record_alloc(size: usize) {
+    let size = size as u64;
+    ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+    ALLOCATED_BYTES.fetch_add(size, Ordering::Relaxed);
+    let current = CURRENT_BYTES.fetch_add(size, Ordering::Relaxed) + size;
+    PEAK_BYTES.fetch_max(current, Ordering::Relaxed);
+}
+
+fn record_dealloc(size: usize) {
+    CURRENT_BYTES.fetch_sub(size as u64, Ordering::Relaxed);
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        record_dealloc(layout.size());
+    }
+
+    // a reallocation counts as a new allocation of the new size that replaces the old one.
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            record_dealloc(layout.size());
+            record_alloc(new_size);
+        }
+        new_ptr
+    }
+}
+
+/// Whether the counting allocator is installed. `dhat-heap` takes precedence over `alloc-stats`.
+#[must_use]
+pub fn is_enabled() -> bool {
+    cfg!(all(feature = "alloc-stats", not(feature = "dhat-heap")))
+}
+
+/// Runs a function and returns its output together with the allocations it made.
+/// Returns no stats if the counting allocator is not installed.
+pub fn measure<T>(func: impl FnOnce() -> T) -> (T, Option<AllocStats>) {
+    if !is_enabled() {
+        return (func(), None);
+    }
+
+    let start_bytes = CURRENT_BYTES.load(Ordering::Relaxed);
+    PEAK_BYTES.store(start_bytes, Ordering::Relaxed);
+    let start_allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let start_allocated_bytes = ALLOCATED_BYTES.load(Ordering::Relaxed);
+
+    let output = func();
+
+    let stats = AllocStats {
+        peak_bytes: PEAK_BYTES
+            .load(Ordering::Relaxed)
+            .saturating_sub(start_bytes),
+        allocations: ALLOCATIONS.load(Ordering::Relaxed) - start_allocations,
+        allocated_bytes: ALLOCATED_BYTES.load(Ordering::Relaxed) - start_allocated_bytes,
+    };
+
+    (output, Some(stats))
+}
+
+/// Formats a number of bytes with a binary unit, e.g. `512 B` or `1.5 KiB`.
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    format!("{value:.1} {}", UNITS[unit])
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[allow(clippy::cast_precision_loss)]
+impl From<&AllocStats> for JsonValue {
+    fn from(value: &AllocStats) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert(
+            "peak_bytes".into(),
+            JsonValue::Number(value.peak_bytes as f64),
+        );
+        map.insert(
+            "allocations".into(),
+            JsonValue::Number(value.allocations as f64),
+        );
+        map.insert(
+            "allocated_bytes".into(),
+            JsonValue::Number(value.allocated_bytes as f64),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for AllocStats {
+    type Error = String;
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected alloc stats to be a JSON object.")?;
+
+        let number = |key: &str| {
+            json.get(key)
+                .and_then(|v| v.get::<f64>())
+                .map(|x| *x as u64)
+                .ok_or(format!("Expected alloc.{key} to be a number."))
+        };
+
+        Ok(AllocStats {
+            peak_bytes: number("peak_bytes")?,
+            allocations: number("allocations")?,
+            allocated_bytes: number("allocated_bytes")?,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use tinyjson::JsonValue;
+
+    use super::{format_bytes, measure, AllocStats};
+
+    #[test]
+    fn formats_bytes() {
+        assert_eq!(format_bytes(0), "0 B");
+        assert_eq!(format_bytes(1023), "1023 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(3 * 1024 * 1024), "3.0 MiB");
+    }
+
+    #[test]
+    fn roundtrips_json_stats() {
+        let stats = AllocStats {
+            peak_bytes: 4096,
+            allocations: 12,
+            allocated_bytes: 8192,
+        };
+        assert_eq!(AllocStats::try_from(&JsonValue::from(&stats)), Ok(stats));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc-stats")]
+    fn measures_allocations() {
+        let (_, stats) = measure(|| {
+            let buffer = vec![0_u8; 4096];
+            drop(buffer);
+            vec![0_u8; 1024]
+        });
+
+        // other tests allocate at the same time, so the counters are lower bounds.
+        let stats = stats.unwrap();
+        assert!(stats.allocations >= 2);
+        assert!(stats.allocated_bytes >= 5120);
+        assert!(stats.peak_bytes >= 4096);
+    }
+
+    #[test]
+    #[cfg(not(feature = "alloc-stats"))]
+    fn skips_measurements_without_allocator() {
+        assert_eq!(measure(|| 1), (1, None));
+    }
+}
@@ -0,0 +1,288 @@
+use std::{
+    collections::HashMap,
+    fs,
+    io::{Error, ErrorKind},
+    str::FromStr,
+};
+use tinyjson::JsonValue;
+
+use crate::template::{paths, PuzzleId};
+
+static ANSWERS_FILE_NAME: &str = "answers.json";
+
+/// Represents the known correct answers for a single puzzle.
+#[derive(Clone, Debug)]
+pub struct Answer {
+    pub puzzle: PuzzleId,
+    pub part_1: Option<String>,
+    pub part_2: Option<String>,
+}
+
+/// Represents the known correct answers for a set of puzzles.
+/// Can be serialized from / to JSON.
+#[derive(Clone, Debug, Default)]
+pub struct Answers {
+    pub data: Vec<Answer>,
+}
+
+impl Answers {
+    /// Dehydrate answers to a JSON file.
+    pub fn store_file(&self) -> Result<(), Error> {
+        self.store_to(&paths::shared_data_file(ANSWERS_FILE_NAME))
+    }
+
+    /// Rehydrate answers from a JSON file. If not present, returns empty answers.
+    /// A file that can not be read or parsed is an error, so it is not overwritten by accident.
+    pub fn read_from_file() -> Result<Self, String> {
+        Self::read_from(&paths::shared_data_file(ANSWERS_FILE_NAME))
+    }
+
+    fn store_to(&self, path: &str) -> Result<(), Error> {
+        let json = JsonValue::from(self.clone());
+        let mut file = fs::File::create(path)?;
+        json.format_to(&mut file)
+    }
+
+    fn read_from(path: &str) -> Result<Self, String> {
+        match fs::read_to_string(path) {
+            Ok(json) => Answers::try_from(json).map_err(|e| format!("could not parse {path}: {e}")),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Answers::default()),
+            Err(e) => Err(format!("could not read {path}: {e}")),
+        }
+    }
+
+    /// Returns the known answer for a puzzle part.
+    pub fn get(&self, puzzle: PuzzleId, part: u8) -> Option<&str> {
+        let answer = self.data.iter().find(|a| a.puzzle == puzzle)?;
+        match part {
+            1 => answer.part_1.as_deref(),
+            2 => answer.part_2.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Sets the answer for a puzzle part, replacing a previous answer if present. Surrounding whitespace is trimmed.
+    pub fn set(&mut self, puzzle: PuzzleId, part: u8, value: &str) {
+        let index = match self.data.iter().position(|a| a.puzzle == puzzle) {
+            Some(index) => index,
+            None => {
+                self.data.push(Answer {
+                    puzzle,
+                    part_1: None,
+                    part_2: None,
+                });
+                self.data.len() - 1
+            }
+        };
+
+        let answer = &mut self.data[index];
+        match part {
+            1 => answer.part_1 = Some(value.trim().to_string()),
+            2 => answer.part_2 = Some(value.trim().to_string()),
+            _ => {}
+        }
+
+        self.data.sort_unstable_by_key(|a| a.puzzle);
+    }
+
+    /// Compares a result against the known answer.
+    /// Returns `None` if no answer is known for this part.
+    pub fn check(&self, puzzle: PuzzleId, part: u8, result: &str) -> Option<bool> {
+        self.get(puzzle, part).map(|answer| answer == result.trim())
+    }
+
+    /// Records an answer in the answers file.
+    pub fn record(puzzle: PuzzleId, part: u8, value: &str) -> Result<(), String> {
+        Self::record_in(
+            &paths::shared_data_file(ANSWERS_FILE_NAME),
+            puzzle,
+            part,
+            value,
+        )
+    }
+
+    fn record_in(path: &str, puzzle: PuzzleId, part: u8, value: &str) -> Result<(), String> {
+        let mut answers = Self::read_from(path)?;
+        answers.set(puzzle, part, value);
+        answers.store_to(path).map_err(|e| e.to_string())
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<Answers> for JsonValue {
+    fn from(value: Answers) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert(
+            "data".into(),
+            JsonValue::Array(value.data.iter().map(JsonValue::from).collect()),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<String> for Answers {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let json = JsonValue::from_str(&value).or(Err("not valid JSON file."))?;
+
+        let json_data = json
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("expected JSON document to be an object.")?
+            .get("data")
+            .ok_or("expected JSON document to have key `data`.")?
+            .get::<Vec<JsonValue>>()
+            .ok_or("expected `json.data` to be an array.")?;
+
+        Ok(Answers {
+            data: json_data
+                .iter()
+                .map(Answer::try_from)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<&Answer> for JsonValue {
+    fn from(value: &Answer) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert("puzzle".into(), JsonValue::String(value.puzzle.to_string()));
+
+        map.insert(
+            "part_1".into(),
+            value
+                .part_1
+                .clone()
+                .map_or(JsonValue::Null, JsonValue::String),
+        );
+
+        map.insert(
+            "part_2".into(),
+            value
+                .part_2
+                .clone()
+                .map_or(JsonValue::Null, JsonValue::String),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for Answer {
+    type Error = String;
+
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected answer to be a JSON object.")?;
+
+        let puzzle = json
+            .get("puzzle")
+            .and_then(|v| v.get::<String>())
+            .and_then(|puzzle| PuzzleId::from_str(puzzle).ok())
+            .ok_or("Expected answer.puzzle to be a PuzzleId.")?;
+
+        let part_1 = json
+            .get("part_1")
+            .map(|v| if v.is_null() { None } else { v.get::<String>() })
+            .ok_or("Expected answer.part_1 to be null or string.")?;
+
+        let part_2 = json
+            .get("part_2")
+            .map(|v| if v.is_null() { None } else { v.get::<String>() })
+            .ok_or("Expected answer.part_2 to be null or string.")?;
+
+        Ok(Answer {
+            puzzle,
+            part_1: part_1.cloned(),
+            part_2: part_2.cloned(),
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::{env, fs};
+
+    use super::{Answer, Answers};
+    use crate::puzzle;
+
+    fn get_mock_answers() -> Answers {
+        Answers {
+            data: vec![Answer {
+                puzzle: puzzle!(2024, 1),
+                part_1: Some("11".into()),
+                part_2: None,
+            }],
+        }
+    }
+
+    #[test]
+    fn checks_known_answers() {
+        let answers = get_mock_answers();
+        assert_eq!(answers.check(puzzle!(2024, 1), 1, "11"), Some(true));
+        assert_eq!(answers.check(puzzle!(2024, 1), 1, "12"), Some(false));
+        assert_eq!(answers.check(puzzle!(2024, 1), 2, "31"), None);
+        assert_eq!(answers.check(puzzle!(2024, 2), 1, "11"), None);
+    }
+
+    #[test]
+    fn sets_answers() {
+        let mut answers = get_mock_answers();
+        answers.set(puzzle!(2024, 1), 2, "31");
+        answers.set(puzzle!(2023, 5), 1, "7");
+        answers.set(puzzle!(2024, 1), 1, "12");
+
+        assert_eq!(answers.data.len(), 2);
+        assert_eq!(answers.data[0].puzzle, puzzle!(2023, 5));
+        assert_eq!(answers.get(puzzle!(2024, 1), 1), Some("12"));
+        assert_eq!(answers.get(puzzle!(2024, 1), 2), Some("31"));
+    }
+
+    #[test]
+    fn trims_set_answers() {
+        let mut answers = get_mock_answers();
+        answers.set(puzzle!(2024, 1), 2, " abc\n");
+
+        assert_eq!(answers.get(puzzle!(2024, 1), 2), Some("abc"));
+        assert_eq!(answers.check(puzzle!(2024, 1), 2, "abc"), Some(true));
+    }
+
+    #[test]
+    fn keeps_malformed_answer_files() {
+        let path = env::temp_dir().join(format!("aoc-answers-{}.json", std::process::id()));
+        let path = path.to_str().unwrap();
+        fs::write(path, "{ not json").unwrap();
+
+        let result = Answers::record_in(path, puzzle!(2024, 1), 1, "11");
+        let content = fs::read_to_string(path).unwrap();
+        fs::remove_file(path).unwrap();
+
+        assert!(result.is_err());
+        assert_eq!(content, "{ not json");
+    }
+
+    #[test]
+    fn handles_json_answers() {
+        let json =
+            r#"{ "data": [{ "puzzle": "2024-01", "part_1": "11", "part_2": null }] }"#.to_string();
+        let answers = Answers::try_from(json).unwrap();
+        assert_eq!(answers.get(puzzle!(2024, 1), 1), Some("11"));
+        assert_eq!(answers.get(puzzle!(2024, 1), 2), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_malformed_answers() {
+        let json = r#"{ "data": [{ "puzzle": "2024-26" }] }"#.to_string();
+        Answers::try_from(json).unwrap();
+    }
+}
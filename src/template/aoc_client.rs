@@ -0,0 +1,324 @@
+/// Native client for the advent of code website.
+///
+/// The session cookie is read from the `AOC_SESSION` environment variable or,
+/// if not set, from the file `~/.adventofcode.session` (compatible with aoc-cli).
+/// The base url can be overridden with the `AOC_BASE_URL` environment variable.
+use std::{
+    env,
+    fmt::Display,
+    fs, io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::template::{html, paths, submissions::SubmissionVerdict, PuzzleId};
+
+const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+const SESSION_FILE_NAME: &str = ".adventofcode.session";
+const USER_AGENT: &str = "github.com/fspoettel/advent-of-code-rust";
+
+/// Returns the public url of a puzzle description, e.g. `https://adventofcode.com/2024/day/1`.
+#[must_use]
+pub fn description_url(puzzle: PuzzleId) -> String {
+    format!(
+        "{DEFAULT_BASE_URL}/{}/day/{}",
+        puzzle.year,
+        puzzle.day.into_inner()
+    )
+}
+
+#[derive(Debug)]
+pub enum AocClientError {
+    SessionNotFound,
+    BadStatus(u16),
+    Transport(String),
+    IO(io::Error),
+}
+
+impl Display for AocClientError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AocClientError::SessionNotFound => write!(
+                f,
+                "no session cookie found. Set `AOC_SESSION` or create the file `~/{SESSION_FILE_NAME}`."
+            ),
+            AocClientError::BadStatus(400) => {
+                write!(f, "server responded with status 400. Is your session cookie valid?")
+            }
+            AocClientError::BadStatus(404) => {
+                write!(f, "server responded with status 404. Is the puzzle unlocked yet?")
+            }
+            AocClientError::BadStatus(status) => {
+                write!(f, "server responded with status {status}.")
+            }
+            AocClientError::Transport(e) => write!(f, "request failed: {e}"),
+            AocClientError::IO(e) => write!(f, "could not write file: {e}"),
+        }
+    }
+}
+
+impl From<io::Error> for AocClientError {
+    fn from(e: io::Error) -> Self {
+        AocClientError::IO(e)
+    }
+}
+
+impl From<ureq::Error> for AocClientError {
+    fn from(e: ureq::Error) -> Self {
+        match e {
+            ureq::Error::Status(status, _) => AocClientError::BadStatus(status),
+            ureq::Error::Transport(t) => AocClientError::Transport(t.to_string()),
+        }
+    }
+}
+
+/// An authenticated connection to the advent of code website.
+pub struct AocClient {
+    agent: ureq::Agent,
+    base_url: String,
+    session: String,
+}
+
+impl AocClient {
+    pub fn new(base_url: &str, session: &str) -> Self {
+        Self {
+            agent: ureq::AgentBuilder::new()
+                .timeout(Duration::from_secs(30))
+                .user_agent(USER_AGENT)
+                .build(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session: session.trim().to_string(),
+        }
+    }
+
+    /// Creates a client from the session cookie and base url found in the environment.
+    pub fn from_env() -> Result<Self, AocClientError> {
+        let base_url = env::var("AOC_BASE_URL").unwrap_or_else(|_| DEFAULT_BASE_URL.into());
+        let session = read_session().ok_or(AocClientError::SessionNotFound)?;
+        Ok(Self::new(&base_url, &session))
+    }
+
+    fn puzzle_url(&self, puzzle: PuzzleId) -> String {
+        format!(
+            "{}/{}/day/{}",
+            self.base_url,
+            puzzle.year,
+            puzzle.day.into_inner()
+        )
+    }
+
+    fn get(&self, url: &str) -> Result<String, AocClientError> {
+        let response = self
+            .agent
+            .get(url)
+            .set("Cookie", &format!("session={}", self.session))
+            .call()?;
+        Ok(response.into_string()?)
+    }
+
+    /// Fetches the puzzle input.
+    pub fn get_input(&self, puzzle: PuzzleId) -> Result<String, AocClientError> {
+        self.get(&format!("{}/input", self.puzzle_url(puzzle)))
+    }
+
+    /// Fetches the puzzle page and converts the description to markdown.
+    pub fn get_puzzle(&self, puzzle: PuzzleId) -> Result<String, AocClientError> {
+        let page = self.get(&self.puzzle_url(puzzle))?;
+        Ok(html::to_markdown(&page))
+    }
+
+    /// Posts an answer and returns the message of the response page.
+    pub fn post_answer(
+        &self,
+        puzzle: PuzzleId,
+        part: u8,
+        answer: &str,
+    ) -> Result<String, AocClientError> {
+        let response = self
+            .agent
+            .post(&format!("{}/answer", self.puzzle_url(puzzle)))
+            .set("Cookie", &format!("session={}", self.session))
+            .send_form(&[("level", &part.to_string()), ("answer", answer)])?;
+
+        let page = response.into_string()?;
+        let message = page
+            .split_once("<article>")
+            .and_then(|(_, rest)| rest.split_once("</article>"))
+            .map_or(page.as_str(), |(article, _)| article);
+
+        Ok(html::strip_tags(message).trim().to_string())
+    }
+}
+
+fn session_file_path() -> Option<PathBuf> {
+    env::var_os("HOME")
+        .or_else(|| env::var_os("USERPROFILE"))
+        .map(|home| PathBuf::from(home).join(SESSION_FILE_NAME))
+}
+
+fn read_session() -> Option<String> {
+    if let Ok(session) = env::var("AOC_SESSION") {
+        if !session.trim().is_empty() {
+            return Some(session);
+        }
+    }
+
+    let session = fs::read_to_string(session_file_path()?).ok()?;
+    if session.trim().is_empty() {
+        None
+    } else {
+        Some(session)
+    }
+}
+
+fn write_file(path: &str, contents: &str) -> Result<(), AocClientError> {
+    if let Some(dir) = Path::new(path).parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, contents)?;
+    Ok(())
+}
+
+/* -------------------------------------------------------------------------- */
+
+pub fn check() -> Result<(), AocClientError> {
+    read_session().ok_or(AocClientError::SessionNotFound)?;
+    Ok(())
+}
+
+/// Downloads the puzzle description to the puzzle path and prints it.
+pub fn read(puzzle: PuzzleId) -> Result<(), AocClientError> {
+    let puzzle_path = paths::puzzle_description(puzzle);
+    let description = AocClient::from_env()?.get_puzzle(puzzle)?;
+    write_file(&puzzle_path, &description)?;
+    println!("{description}");
+    Ok(())
+}
+
+pub fn download(puzzle: PuzzleId) -> Result<(), AocClientError> {
+    let input_path = paths::input(puzzle);
+    let puzzle_path = paths::puzzle_description(puzzle);
+
+    let client = AocClient::from_env()?;
+    write_file(&input_path, &client.get_input(puzzle)?)?;
+    write_file(&puzzle_path, &client.get_puzzle(puzzle)?)?;
+
+    println!("🎄 Successfully wrote input to \"{}\".", &input_path);
+    println!("🎄 Successfully wrote puzzle to \"{}\".", &puzzle_path);
+    Ok(())
+}
+
+pub fn submit(
+    puzzle: PuzzleId,
+    part: u8,
+    result: &str,
+) -> Result<SubmissionVerdict, AocClientError> {
+    let message = AocClient::from_env()?.post_answer(puzzle, part, result)?;
+    Ok(SubmissionVerdict::from_message(&message))
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        sync::mpsc,
+        thread,
+    };
+
+    use super::{AocClient, AocClientError};
+    use crate::puzzle;
+
+    /// Serves a single request with a canned response and sends the raw request back to the test.
+    fn stub_server(status: u16, body: &'static str) -> (String, mpsc::Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let (tx, rx) = mpsc::channel();
+
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+            let mut request = String::new();
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if let Some(len) = line.to_lowercase().strip_prefix("content-length:") {
+                    content_length = len.trim().parse().unwrap();
+                }
+                request.push_str(&line);
+                if line == "\r\n" {
+                    break;
+                }
+            }
+
+            let mut body_buf = vec![0; content_length];
+            reader.read_exact(&mut body_buf).unwrap();
+            request.push_str(&String::from_utf8(body_buf).unwrap());
+
+            write!(
+                stream,
+                "HTTP/1.1 {status} OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            )
+            .unwrap();
+
+            tx.send(request).unwrap();
+        });
+
+        (base_url, rx)
+    }
+
+    #[test]
+    fn fetches_input() {
+        let (base_url, rx) = stub_server(200, "1 2\n3 4\n");
+        let client = AocClient::new(&base_url, "abc\n");
+
+        let input = client.get_input(puzzle!(2024, 1)).unwrap();
+        assert_eq!(input, "1 2\n3 4\n");
+
+        let request = rx.recv().unwrap();
+        assert!(request.starts_with("GET /2024/day/1/input "));
+        assert!(request.contains("session=abc\r\n"));
+    }
+
+    #[test]
+    fn fetches_puzzle() {
+        let (base_url, _rx) = stub_server(
+            200,
+            "<main><article><h2>--- Day 5 ---</h2><p>Hello</p></article></main>",
+        );
+        let client = AocClient::new(&base_url, "abc");
+
+        let description = client.get_puzzle(puzzle!(2023, 5)).unwrap();
+        assert_eq!(description, "## --- Day 5 ---\n\nHello\n");
+    }
+
+    #[test]
+    fn posts_answers() {
+        let (base_url, rx) = stub_server(
+            200,
+            "<main><article><p>That's the right answer!</p></article></main>",
+        );
+        let client = AocClient::new(&base_url, "abc");
+
+        let message = client.post_answer(puzzle!(2024, 1), 2, "42").unwrap();
+        assert_eq!(message, "That's the right answer!");
+
+        let request = rx.recv().unwrap();
+        assert!(request.starts_with("POST /2024/day/1/answer "));
+        assert!(request.ends_with("level=2&answer=42"));
+    }
+
+    #[test]
+    fn reports_bad_status() {
+        let (base_url, _rx) = stub_server(400, "");
+        let client = AocClient::new(&base_url, "abc");
+
+        let result = client.get_input(puzzle!(2024, 1));
+        assert!(matches!(result, Err(AocClientError::BadStatus(400))));
+    }
+}
@@ -0,0 +1,326 @@
+/// Benchmarks solution parts and summarizes the measured samples.
+use std::collections::HashMap;
+use std::env;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+use tinyjson::JsonValue;
+
+use crate::template::config::config;
+
+/// Minimum number of samples taken, regardless of the time budget.
+const MIN_SAMPLES: u128 = 10;
+
+/// Results with a relative standard deviation above this threshold are considered noisy.
+const NOISE_THRESHOLD: f64 = 0.1;
+
+/// Standard deviations below this are within timer resolution and never considered noisy.
+const NOISE_FLOOR: Duration = Duration::from_nanos(100);
+
+/// Configures how long a solution part is benched.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BenchConfig {
+    /// Approximate time spent measuring a part.
+    pub budget: Duration,
+    /// Maximum number of samples taken.
+    pub max_samples: u128,
+    /// Time spent running a part before measuring it.
+    pub warmup: Duration,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        BenchConfig {
+            budget: Duration::from_secs(1),
+            max_samples: 10_000,
+            warmup: Duration::from_millis(100),
+        }
+    }
+}
+
+impl BenchConfig {
+    /// Reads the config from the `AOC_BENCH_BUDGET_MS`, `AOC_BENCH_MAX_SAMPLES` and `AOC_BENCH_WARMUP_MS` environment
+    /// variables, falling back to the `[bench]` section of `aoc.toml` for missing values.
+    pub fn from_env() -> Self {
+        fn var<T: std::str::FromStr>(name: &str) -> Option<T> {
+            env::var(name).ok().and_then(|v| v.parse().ok())
+        }
+
+        let default = config().bench;
+
+        BenchConfig {
+            budget: var("AOC_BENCH_BUDGET_MS").map_or(default.budget, Duration::from_millis),
+            max_samples: var("AOC_BENCH_MAX_SAMPLES")
+                .map_or(default.max_samples, |n: u128| n.max(1)),
+            warmup: var("AOC_BENCH_WARMUP_MS").map_or(default.warmup, Duration::from_millis),
+        }
+    }
+
+    /// Sets the environment variables read by [`BenchConfig::from_env`], so child processes inherit the config.
+    pub fn export_env(&self) {
+        env::set_var("AOC_BENCH_BUDGET_MS", self.budget.as_millis().to_string());
+        env::set_var("AOC_BENCH_MAX_SAMPLES", self.max_samples.to_string());
+        env::set_var("AOC_BENCH_WARMUP_MS", self.warmup.as_millis().to_string());
+    }
+
+    /// Number of iterations that fit into a time budget, based on the duration of a single run.
+    fn iterations(budget: Duration, base_time: &Duration, min: u128, max: u128) -> u128 {
+        (budget.as_nanos() / base_time.as_nanos().max(10)).clamp(min.min(max), max)
+    }
+}
+
+/// Summary statistics of a benchmark.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BenchStats {
+    pub samples: u128,
+    pub mean: Duration,
+    pub median: Duration,
+    pub min: Duration,
+    pub max: Duration,
+    pub std_dev: Duration,
+    /// Number of samples outside of the inner fences (1.5 IQR) of the distribution.
+    pub outliers: u128,
+}
+
+impl BenchStats {
+    /// Summarizes a non-empty list of samples.
+    pub fn from_samples(samples: &[Duration]) -> Self {
+        let mut sorted: Vec<u128> = samples.iter().map(Duration::as_nanos).collect();
+        sorted.sort_unstable();
+
+        let count = sorted.len() as u128;
+        let mean = sorted.iter().sum::<u128>() / count;
+
+        #[allow(clippy::cast_precision_loss)]
+        let variance = sorted
+            .iter()
+            .map(|&x| (x as f64 - mean as f64).powi(2))
+            .sum::<f64>()
+            / count as f64;
+
+        let q1 = quantile(&sorted, 0.25);
+        let q3 = quantile(&sorted, 0.75);
+        let iqr = q3 - q1;
+        let (low, high) = (q1 - 1.5 * iqr, q3 + 1.5 * iqr);
+
+        #[allow(clippy::cast_precision_loss)]
+        let outliers = sorted
+            .iter()
+            .filter(|&&x| (x as f64) < low || (x as f64) > high)
+            .count() as u128;
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        BenchStats {
+            samples: count,
+            mean: nanos(mean),
+            median: Duration::from_nanos(quantile(&sorted, 0.5) as u64),
+            min: nanos(sorted[0]),
+            max: nanos(sorted[sorted.len() - 1]),
+            std_dev: Duration::from_nanos(variance.sqrt() as u64),
+            outliers,
+        }
+    }
+
+    /// Standard deviation relative to the mean.
+    pub fn relative_std_dev(&self) -> f64 {
+        if self.mean.is_zero() {
+            0.0
+        } else {
+            self.std_dev.as_secs_f64() / self.mean.as_secs_f64()
+        }
+    }
+
+    /// Whether the samples vary too much for the mean to be reliable.
+    pub fn is_noisy(&self) -> bool {
+        self.std_dev >= NOISE_FLOOR && self.relative_std_dev() > NOISE_THRESHOLD
+    }
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn nanos(value: u128) -> Duration {
+    Duration::from_nanos(value as u64)
+}
+
+/// Linearly interpolated quantile of sorted values.
+#[allow(clippy::cast_precision_loss)]
+fn quantile(sorted: &[u128], q: f64) -> f64 {
+    let position = (sorted.len() - 1) as f64 * q;
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let index = position.floor() as usize;
+    let fraction = position - position.floor();
+
+    match sorted.get(index + 1) {
+        Some(&next) => sorted[index] as f64 + (next as f64 - sorted[index] as f64) * fraction,
+        None => sorted[index] as f64,
+    }
+}
+
+/// Benches a function. Warms up for `config.warmup` (skipped if zero), then measures samples for about
+/// `config.budget`, taking at least 10 and at most `config.max_samples` samples.
+pub fn bench<I: Copy, T>(
+    func: impl Fn(I) -> T,
+    input: I,
+    base_time: &Duration,
+    config: &BenchConfig,
+) -> BenchStats {
+    let warmup_iterations =
+        BenchConfig::iterations(config.warmup, base_time, 0, config.max_samples);
+
+    for _ in 0..warmup_iterations {
+        black_box(func(black_box(input)));
+    }
+
+    let bench_iterations =
+        BenchConfig::iterations(config.budget, base_time, MIN_SAMPLES, config.max_samples);
+
+    let mut timers: Vec<Duration> = vec![];
+
+    for _ in 0..bench_iterations {
+        let timer = Instant::now();
+        black_box(func(black_box(input)));
+        timers.push(timer.elapsed());
+    }
+
+    BenchStats::from_samples(&timers)
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[allow(clippy::cast_precision_loss)]
+impl From<&BenchStats> for JsonValue {
+    fn from(value: &BenchStats) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        let mut insert = |key: &str, nanos: u128| {
+            map.insert(key.into(), JsonValue::Number(nanos as f64));
+        };
+
+        insert("samples", value.samples);
+        insert("mean_nanos", value.mean.as_nanos());
+        insert("median_nanos", value.median.as_nanos());
+        insert("min_nanos", value.min.as_nanos());
+        insert("max_nanos", value.max.as_nanos());
+        insert("std_dev_nanos", value.std_dev.as_nanos());
+        insert("outliers", value.outliers);
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for BenchStats {
+    type Error = String;
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected stats to be a JSON object.")?;
+
+        let number = |key: &str| {
+            json.get(key)
+                .and_then(|v| v.get::<f64>())
+                .map(|x| *x as u64)
+                .ok_or(format!("Expected stats.{key} to be a number."))
+        };
+
+        Ok(BenchStats {
+            samples: u128::from(number("samples")?),
+            mean: Duration::from_nanos(number("mean_nanos")?),
+            median: Duration::from_nanos(number("median_nanos")?),
+            min: Duration::from_nanos(number("min_nanos")?),
+            max: Duration::from_nanos(number("max_nanos")?),
+            std_dev: Duration::from_nanos(number("std_dev_nanos")?),
+            outliers: u128::from(number("outliers")?),
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::{cell::Cell, time::Duration};
+
+    use super::{bench, BenchConfig, BenchStats};
+    use tinyjson::JsonValue;
+
+    fn samples(nanos: &[u64]) -> Vec<Duration> {
+        nanos.iter().map(|&n| Duration::from_nanos(n)).collect()
+    }
+
+    #[test]
+    fn summarizes_samples() {
+        let stats = BenchStats::from_samples(&samples(&[10, 12, 11, 13, 100, 9, 10, 11]));
+
+        assert_eq!(stats.samples, 8);
+        assert_eq!(stats.mean, Duration::from_nanos(22));
+        assert_eq!(stats.median, Duration::from_nanos(11));
+        assert_eq!(stats.min, Duration::from_nanos(9));
+        assert_eq!(stats.max, Duration::from_nanos(100));
+        assert_eq!(stats.outliers, 1);
+        // the deviation is within timer resolution.
+        assert!(!stats.is_noisy());
+    }
+
+    #[test]
+    fn detects_noisy_samples() {
+        let stats =
+            BenchStats::from_samples(&samples(&[1000, 1200, 1100, 1300, 10_000, 900, 1000, 1100]));
+        assert!(stats.is_noisy());
+    }
+
+    #[test]
+    fn detects_stable_samples() {
+        let stats = BenchStats::from_samples(&samples(&[100, 101, 99, 100]));
+        assert_eq!(stats.outliers, 0);
+        assert_eq!(stats.std_dev, Duration::from_nanos(0));
+        assert!(!stats.is_noisy());
+    }
+
+    #[test]
+    fn clamps_iterations() {
+        let base = Duration::from_millis(1);
+        assert_eq!(
+            BenchConfig::iterations(Duration::from_secs(1), &base, 10, 10_000),
+            1000
+        );
+        assert_eq!(
+            BenchConfig::iterations(Duration::from_secs(1), &base, 10, 100),
+            100
+        );
+        assert_eq!(
+            BenchConfig::iterations(Duration::from_millis(1), &base, 10, 10_000),
+            10
+        );
+        assert_eq!(
+            BenchConfig::iterations(Duration::from_millis(1), &base, 10, 5),
+            5
+        );
+    }
+
+    #[test]
+    fn skips_warmup_if_zero() {
+        let config = BenchConfig {
+            budget: Duration::ZERO,
+            max_samples: 10_000,
+            warmup: Duration::ZERO,
+        };
+        let runs = Cell::new(0);
+
+        let stats = bench(
+            |()| runs.set(runs.get() + 1),
+            (),
+            &Duration::from_millis(1),
+            &config,
+        );
+
+        assert_eq!(stats.samples, 10);
+        assert_eq!(runs.get(), 10);
+    }
+
+    #[test]
+    fn roundtrips_json_stats() {
+        let stats = BenchStats::from_samples(&samples(&[10, 12, 11, 13, 100]));
+        let parsed = BenchStats::try_from(&JsonValue::from(&stats)).unwrap();
+        assert_eq!(stats, parsed);
+    }
+}
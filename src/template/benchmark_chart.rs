@@ -0,0 +1,289 @@
+/// Renders benchmark timings as a self-contained SVG bar chart.
+///
+/// The chart has a group of bars per day and one bar per part. It is rendered without external resources,
+/// so it can be regenerated anywhere, and the output only depends on the timings.
+use std::{fmt::Write, time::Duration};
+
+use crate::template::{config::ChartScale, runner::PARSE_STEP, timings::Timings, Year};
+
+const PLOT_HEIGHT: f64 = 200.0;
+/// Leaves room for the title and legend if only a few days are solved.
+const MIN_WIDTH: f64 = 320.0;
+const MARGIN_LEFT: f64 = 64.0;
+const MARGIN_RIGHT: f64 = 16.0;
+const MARGIN_TOP: f64 = 48.0;
+const MARGIN_BOTTOM: f64 = 32.0;
+const BAR_WIDTH: f64 = 10.0;
+const BAR_GAP: f64 = 2.0;
+const GROUP_GAP: f64 = 12.0;
+
+/// Parse step, part one and part two.
+const SERIES: [(u8, &str, &str); 3] = [
+    (PARSE_STEP, "Parse", "#7f8c8d"),
+    (1, "Part 1", "#c0392b"),
+    (2, "Part 2", "#27ae60"),
+];
+
+/// Maps durations in nanoseconds to bar heights.
+struct Axis {
+    scale: ChartScale,
+    min: f64,
+    max: f64,
+}
+
+impl Axis {
+    #[allow(clippy::cast_precision_loss)]
+    fn new(scale: ChartScale, values: &[f64]) -> Self {
+        let max = values.iter().copied().fold(1.0, f64::max);
+
+        match scale {
+            ChartScale::Linear => {
+                // round the top of the axis up to a multiple of 1, 2 or 5 so the ticks are readable.
+                let raw_step = max / 4.0;
+                let magnitude = 10_f64.powf(raw_step.log10().floor());
+                let step = [1.0, 2.0, 5.0, 10.0]
+                    .into_iter()
+                    .map(|factor| factor * magnitude)
+                    .find(|step| *step >= raw_step)
+                    .unwrap_or(10.0 * magnitude);
+
+                Axis {
+                    scale,
+                    min: 0.0,
+                    max: step * 4.0,
+                }
+            }
+            ChartScale::Log => {
+                let min = values
+                    .iter()
+                    .copied()
+                    .filter(|value| *value >= 1.0)
+                    .fold(max, f64::min);
+                let min = 10_f64.powf(min.log10().floor());
+                let max = 10_f64.powf(max.log10().ceil()).max(min * 10.0);
+
+                Axis { scale, min, max }
+            }
+        }
+    }
+
+    fn ticks(&self) -> Vec<f64> {
+        match self.scale {
+            ChartScale::Linear => (0..=4).map(|i| self.max / 4.0 * f64::from(i)).collect(),
+            ChartScale::Log => {
+                let mut ticks = vec![self.min];
+                while ticks.last().is_some_and(|tick| *tick < self.max) {
+                    ticks.push(ticks.last().unwrap() * 10.0);
+                }
+                ticks
+            }
+        }
+    }
+
+    /// Returns the height of a bar, between `0` and `PLOT_HEIGHT`.
+    fn height(&self, value: f64) -> f64 {
+        let fraction = match self.scale {
+            ChartScale::Linear => value / self.max,
+            ChartScale::Log if value < self.min => 0.0,
+            ChartScale::Log => {
+                (value.log10() - self.min.log10()) / (self.max.log10() - self.min.log10())
+            }
+        };
+
+        round(fraction.clamp(0.0, 1.0) * PLOT_HEIGHT)
+    }
+}
+
+/// Rounds a coordinate to one decimal, which is precise enough and keeps the document small.
+fn round(value: f64) -> f64 {
+    (value * 10.0).round() / 10.0
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn format_nanos(nanos: f64) -> String {
+    format!("{:?}", Duration::from_nanos(nanos.round() as u64))
+}
+
+/// Renders the timings of a year as an SVG document. Returns `None` if there are no timings.
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+pub fn render(year: Year, timings: &Timings, scale: ChartScale) -> Option<String> {
+    if timings.data.is_empty() {
+        return None;
+    }
+
+    // only show the parse series if at least one solution has a parse step.
+    let series: Vec<_> = SERIES
+        .into_iter()
+        .filter(|(part, _, _)| timings.data.iter().any(|t| t.part(*part).is_some()))
+        .collect();
+
+    let values: Vec<f64> = timings
+        .data
+        .iter()
+        .flat_map(|t| series.iter().filter_map(|(part, _, _)| t.part(*part)))
+        .map(|timing| timing.duration.as_nanos() as f64)
+        .collect();
+
+    let axis = Axis::new(scale, &values);
+
+    let bars = series.len().max(1) as f64;
+    let group_width = bars * BAR_WIDTH + (bars - 1.0) * BAR_GAP + GROUP_GAP;
+    let width =
+        (MARGIN_LEFT + group_width * timings.data.len() as f64 + MARGIN_RIGHT).max(MIN_WIDTH);
+    let height = MARGIN_TOP + PLOT_HEIGHT + MARGIN_BOTTOM;
+    let baseline = MARGIN_TOP + PLOT_HEIGHT;
+
+    let mut svg = String::new();
+
+    // writing to a string does not fail.
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}" font-family="sans-serif" font-size="11">"#
+    );
+    let _ = writeln!(
+        svg,
+        r##"<rect width="{width}" height="{height}" rx="6" fill="#ffffff"/>"##
+    );
+
+    let scale_name = match scale {
+        ChartScale::Linear => "",
+        ChartScale::Log => " (log scale)",
+    };
+    let _ = writeln!(
+        svg,
+        r##"<text x="{MARGIN_LEFT}" y="20" font-size="14" font-weight="bold" fill="#333333">{year} Benchmarks{scale_name}</text>"##
+    );
+
+    for (i, (_, name, color)) in series.iter().enumerate() {
+        let x = MARGIN_LEFT + 72.0 * i as f64;
+        let _ = writeln!(
+            svg,
+            r##"<rect x="{x}" y="28" width="10" height="10" fill="{color}"/><text x="{}" y="37" fill="#333333">{name}</text>"##,
+            x + 14.0
+        );
+    }
+
+    for tick in axis.ticks() {
+        let y = round(baseline - axis.height(tick));
+        let _ = writeln!(
+            svg,
+            r##"<line x1="{MARGIN_LEFT}" y1="{y}" x2="{}" y2="{y}" stroke="#e5e5e5"/><text x="{}" y="{}" text-anchor="end" fill="#555555">{}</text>"##,
+            width - MARGIN_RIGHT,
+            MARGIN_LEFT - 6.0,
+            y + 4.0,
+            format_nanos(tick)
+        );
+    }
+
+    for (i, timing) in timings.data.iter().enumerate() {
+        let group_x = MARGIN_LEFT + GROUP_GAP / 2.0 + group_width * i as f64;
+
+        for (j, (part, name, color)) in series.iter().enumerate() {
+            let Some(part_timing) = timing.part(*part) else {
+                continue;
+            };
+
+            let bar_height = axis.height(part_timing.duration.as_nanos() as f64);
+            let _ = writeln!(
+                svg,
+                r#"<rect x="{}" y="{}" width="{BAR_WIDTH}" height="{bar_height}" fill="{color}"><title>Day {} {name}: {part_timing}</title></rect>"#,
+                group_x + (BAR_WIDTH + BAR_GAP) * j as f64,
+                round(baseline - bar_height),
+                timing.puzzle.day.into_inner(),
+            );
+        }
+
+        let _ = writeln!(
+            svg,
+            r##"<text x="{}" y="{}" text-anchor="middle" fill="#555555">{}</text>"##,
+            group_x + (group_width - GROUP_GAP) / 2.0,
+            baseline + 16.0,
+            timing.puzzle.day.into_inner()
+        );
+    }
+
+    let _ = writeln!(
+        svg,
+        r##"<line x1="{MARGIN_LEFT}" y1="{baseline}" x2="{}" y2="{baseline}" stroke="#999999"/>"##,
+        width - MARGIN_RIGHT
+    );
+    svg.push_str("</svg>\n");
+
+    Some(svg)
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::time::Duration;
+
+    use super::render;
+    use crate::{
+        puzzle,
+        template::{
+            config::ChartScale,
+            timings::{PartTiming, Timing, Timings},
+        },
+        year,
+    };
+
+    fn timings() -> Timings {
+        let part = |micros| Some(PartTiming::new(Duration::from_micros(micros), None));
+
+        Timings {
+            data: vec![
+                Timing {
+                    puzzle: puzzle!(2024, 1),
+                    parse: None,
+                    part_1: part(10),
+                    part_2: part(2000),
+                    limit: None,
+                },
+                Timing {
+                    puzzle: puzzle!(2024, 2),
+                    parse: None,
+                    part_1: part(300),
+                    part_2: None,
+                    limit: None,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn renders_bars() {
+        let svg = render(year!(2024), &timings(), ChartScale::Linear).unwrap();
+
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("<title>").count(), 3);
+        assert!(svg.contains("<title>Day 1 Part 2: 2.0ms</title>"));
+        assert!(!svg.contains("Parse"));
+        // ticks of the linear axis, rounded up from 2ms.
+        assert!(svg.contains(">0ns</text>"));
+        assert!(svg.contains(">2ms</text>"));
+    }
+
+    #[test]
+    fn renders_log_scale() {
+        let svg = render(year!(2024), &timings(), ChartScale::Log).unwrap();
+
+        assert!(svg.contains("(log scale)"));
+        for tick in ["10µs", "100µs", "1ms", "10ms"] {
+            assert!(
+                svg.contains(&format!(">{tick}</text>")),
+                "missing tick {tick}"
+            );
+        }
+    }
+
+    #[test]
+    fn skips_empty_timings() {
+        assert_eq!(
+            render(year!(2024), &Timings::default(), ChartScale::Log),
+            None
+        );
+    }
+}
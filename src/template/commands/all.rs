@@ -1,5 +1,44 @@
-use crate::template::{all_days, run_multi::run_multi};
+use std::process;
 
-pub fn handle(is_release: bool) {
-    run_multi(&all_days().collect(), is_release, false);
+use crate::template::limits::Limits;
+use crate::template::run_multi::{child_commands, run_multi};
+use crate::template::test_report::ReportTarget;
+use crate::template::{all_puzzles, Year};
+
+pub fn handle(
+    year: Year,
+    is_release: bool,
+    is_isolated: bool,
+    jobs: usize,
+    limits: &Limits,
+    report: Option<&ReportTarget>,
+) {
+    // the `solutions` binary reads the limits from the environment it inherits.
+    limits.export_env();
+
+    let puzzles = all_puzzles(year).collect();
+
+    if is_isolated {
+        let run = run_multi(&puzzles, is_release, false, jobs, limits);
+        let report_failed = report.is_some_and(|report| !report.write(&run.days));
+
+        if run.has_failures() || report_failed {
+            process::exit(1);
+        }
+    } else {
+        let jobs = jobs.to_string();
+        let report_args = report.map(ReportTarget::args);
+
+        let mut args = vec!["--jobs", &jobs];
+        args.extend(report_args.iter().flatten().map(String::as_str));
+
+        match child_commands::run_solutions(&puzzles, is_release, &args) {
+            Ok(status) if status.success() => {}
+            Ok(status) => process::exit(status.code().unwrap_or(1)),
+            Err(e) => {
+                eprintln!("Failed to run solutions: {e:?}");
+                process::exit(1);
+            }
+        }
+    }
 }
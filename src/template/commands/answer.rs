@@ -0,0 +1,17 @@
+use std::process;
+
+use crate::template::{answers::Answers, PuzzleId};
+
+pub fn handle(puzzle: PuzzleId, part: u8, value: &str) {
+    if part != 1 && part != 2 {
+        eprintln!("Expecting part to be 1 or 2.");
+        process::exit(1);
+    }
+
+    if let Err(e) = Answers::record(puzzle, part, value) {
+        eprintln!("Failed to store answer: {e}");
+        process::exit(1);
+    }
+
+    println!("Stored answer `{value}` for {puzzle} part {part}.");
+}
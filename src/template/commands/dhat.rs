@@ -0,0 +1,148 @@
+use std::process;
+
+use crate::template::{
+    alloc_stats::format_bytes,
+    dhat_profile::{solution_frames, Profile, Usage, DEFAULT_PROFILE},
+    ANSI_BOLD, ANSI_RESET,
+};
+
+/// Number of allocation sites that are listed by default.
+pub const DEFAULT_TOP: usize = 5;
+
+/// Number of frames that are shown per allocation site.
+const SITE_FRAMES: usize = 3;
+
+/// Summarizes a heap profile, or compares it against a second profile if `other` is passed.
+/// Defaults to the profile of the last `cargo solve <day> --dhat`.
+pub fn handle(profile: Option<&str>, other: Option<&str>, top: usize) {
+    let profile = profile.unwrap_or(DEFAULT_PROFILE);
+    let result = match other {
+        Some(other) => print_diff(profile, other, top),
+        None => print_summary(profile, top),
+    };
+
+    if let Err(e) = result {
+        eprintln!("{e}");
+        process::exit(1);
+    }
+}
+
+/// Prints the totals of a heap profile and its largest allocation sites by bytes and by count.
+pub fn print_summary(path: &str, top: usize) -> Result<(), String> {
+    let profile = Profile::read_from_file(path)?;
+    let total = profile.total();
+
+    println!();
+    println!("{ANSI_BOLD}Heap profile{ANSI_RESET} ({path})");
+    println!("Total:   {}", format_usage(total));
+    println!("At peak: {}", format_usage(profile.peak()));
+    println!("At end:  {}", format_usage(profile.end()));
+
+    let sections = [
+        ("Top sites by bytes", profile.top_by_bytes(top)),
+        ("Top sites by count", profile.top_by_blocks(top)),
+    ];
+
+    for (title, sites) in sections {
+        if sites.is_empty() {
+            continue;
+        }
+
+        println!();
+        println!("{ANSI_BOLD}{title}{ANSI_RESET}");
+
+        for (i, site) in sites.iter().enumerate() {
+            println!(
+                "{:>2}. {} ({} of total bytes, {} at peak)",
+                i + 1,
+                format_usage(site.total),
+                format_share(site.total.bytes, total.bytes),
+                format_bytes(site.peak.bytes),
+            );
+            print_frames(&site.frames);
+        }
+    }
+
+    Ok(())
+}
+
+/// Prints how the totals and the allocation sites changed between two heap profiles.
+fn print_diff(before_path: &str, after_path: &str, top: usize) -> Result<(), String> {
+    let before = Profile::read_from_file(before_path)?;
+    let after = Profile::read_from_file(after_path)?;
+
+    println!();
+    println!("{ANSI_BOLD}Heap profile diff{ANSI_RESET} ({before_path} -> {after_path})");
+
+    for (name, before_usage, after_usage) in [
+        ("Total:  ", before.total(), after.total()),
+        ("At peak:", before.peak(), after.peak()),
+        ("At end: ", before.end(), after.end()),
+    ] {
+        println!(
+            "{name} {} -> {} ({} bytes, {} blocks)",
+            format_usage(before_usage),
+            format_usage(after_usage),
+            format_change(before_usage.bytes, after_usage.bytes),
+            format_change(before_usage.blocks, after_usage.blocks),
+        );
+    }
+
+    let diffs = before.diff(&after);
+
+    println!();
+
+    if diffs.is_empty() {
+        println!("No allocation site changed.");
+        return Ok(());
+    }
+
+    println!("{ANSI_BOLD}Largest changes{ANSI_RESET}");
+
+    for (i, diff) in diffs.iter().take(top).enumerate() {
+        let bytes = diff.bytes_change();
+        println!(
+            "{:>2}. {}{}, {:+} blocks ({} -> {})",
+            i + 1,
+            if bytes < 0 { "-" } else { "+" },
+            format_bytes(bytes.unsigned_abs()),
+            diff.blocks_change(),
+            format_usage(diff.before),
+            format_usage(diff.after),
+        );
+        print_frames(&diff.frames);
+    }
+
+    if diffs.len() > top {
+        println!("... and {} more changed site(s).", diffs.len() - top);
+    }
+
+    Ok(())
+}
+
+fn print_frames(frames: &[String]) {
+    for frame in solution_frames(frames, SITE_FRAMES) {
+        println!("      {frame}");
+    }
+}
+
+fn format_usage(usage: Usage) -> String {
+    format!("{} in {} blocks", format_bytes(usage.bytes), usage.blocks)
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn format_share(value: u64, total: u64) -> String {
+    format!("{:.1}%", value as f64 / total.max(1) as f64 * 100.0)
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn format_change(before: u64, after: u64) -> String {
+    if before == 0 {
+        return if after == 0 {
+            "±0%".into()
+        } else {
+            "new".into()
+        };
+    }
+    format!("{:+.1}%", (after as f64 / before as f64 - 1.0) * 100.0)
+}
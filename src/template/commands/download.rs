@@ -1,14 +1,15 @@
-use crate::template::{aoc_cli, Day};
+use crate::template::{aoc_client, PuzzleId};
 use std::process;
 
-pub fn handle(day: Day) {
-    if aoc_cli::check().is_err() {
-        eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
+pub fn handle(puzzle: PuzzleId) {
+    if let Err(e) = try_download(puzzle) {
+        eprintln!("{e}");
         process::exit(1);
     }
+}
 
-    if let Err(e) = aoc_cli::download(day) {
-        eprintln!("failed to call aoc-cli: {e}");
-        process::exit(1);
-    };
+/// Downloads the input and the description of a puzzle, returning the error instead of exiting.
+pub fn try_download(puzzle: PuzzleId) -> Result<(), String> {
+    aoc_client::check().map_err(|e| e.to_string())?;
+    aoc_client::download(puzzle).map_err(|e| format!("failed to download puzzle: {e}"))
 }
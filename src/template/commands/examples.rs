@@ -0,0 +1,183 @@
+/// Writes example files and records answers from a downloaded puzzle description.
+/// Works offline on the description written by `cargo download` or `cargo read`.
+use std::{
+    fs,
+    io::{self, IsTerminal, Write},
+    path::Path,
+    process,
+};
+
+use crate::template::{
+    answers::Answers,
+    extract::{self, CodeBlock},
+    paths, PuzzleId, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET,
+};
+
+/// Number of lines shown for each code block.
+const PREVIEW_LINES: usize = 5;
+
+pub fn handle(puzzle: PuzzleId, write: Option<Vec<usize>>, overwrite: bool) {
+    let description_path = paths::puzzle_description(puzzle);
+
+    let Ok(description) = fs::read_to_string(&description_path) else {
+        eprintln!(
+            "Could not read \"{description_path}\". Run `cargo download {} --year {}` first.",
+            puzzle.day, puzzle.year
+        );
+        process::exit(1);
+    };
+
+    let blocks = extract::code_blocks(&description);
+
+    if blocks.is_empty() {
+        println!("{ANSI_ITALIC}No code blocks found in \"{description_path}\".{ANSI_RESET}");
+    } else {
+        print_blocks(&blocks);
+
+        let selection = write.unwrap_or_else(|| prompt_selection(&blocks));
+
+        if let Some(block) = selection.iter().find(|&&i| i == 0 || i > blocks.len()) {
+            eprintln!("Block {block} does not exist.");
+            process::exit(1);
+        }
+
+        for (i, block) in selection.iter().enumerate() {
+            let path = match i {
+                0 => paths::example(puzzle),
+                _ => paths::extra_example(puzzle, i + 1),
+            };
+            write_example(&path, &blocks[block - 1].content, overwrite);
+        }
+    }
+
+    record_answers(puzzle, &extract::answers(&description));
+}
+
+/// Parses a comma-separated list of block numbers, e.g. `1,3`.
+pub fn parse_selection(s: &str) -> Result<Vec<usize>, String> {
+    s.split(',')
+        .map(str::trim)
+        .filter(|x| !x.is_empty())
+        .map(|x| {
+            x.parse()
+                .map_err(|_| format!("`{x}` is not a block number."))
+        })
+        .collect()
+}
+
+fn print_blocks(blocks: &[CodeBlock]) {
+    for (i, block) in blocks.iter().enumerate() {
+        let hint = if block.is_example { " (example)" } else { "" };
+        println!("{ANSI_BOLD}Block {}{hint}{ANSI_RESET}", i + 1);
+
+        let lines: Vec<&str> = block.content.lines().collect();
+        for line in lines.iter().take(PREVIEW_LINES) {
+            println!("  {line}");
+        }
+        if lines.len() > PREVIEW_LINES {
+            println!(
+                "  {ANSI_ITALIC}... {} more lines{ANSI_RESET}",
+                lines.len() - PREVIEW_LINES
+            );
+        }
+    }
+}
+
+/// Asks which blocks should be written, defaulting to the first block that follows a mention of an example.
+fn prompt_selection(blocks: &[CodeBlock]) -> Vec<usize> {
+    if !io::stdin().is_terminal() {
+        println!("Pass `--write <blocks>`, e.g. `--write 1,3`, to write example files.");
+        return vec![];
+    }
+
+    let default = blocks.iter().position(|b| b.is_example).map(|i| i + 1);
+
+    loop {
+        print!(
+            "Blocks to write as example files, e.g. `1,3` [{}, `-` to skip]: ",
+            default.map_or("none".into(), |i| i.to_string())
+        );
+        io::stdout().flush().unwrap();
+
+        let mut line = String::new();
+        if io::stdin().read_line(&mut line).is_err() {
+            return vec![];
+        }
+
+        match line.trim() {
+            "" => return default.into_iter().collect(),
+            "-" => return vec![],
+            selection => match parse_selection(selection) {
+                Ok(selection) => return selection,
+                Err(e) => eprintln!("{e}"),
+            },
+        }
+    }
+}
+
+fn write_example(path: &str, content: &str, overwrite: bool) {
+    let is_empty = fs::read_to_string(path).map_or(true, |s| s.trim().is_empty());
+
+    if !is_empty && !overwrite {
+        println!("Skipped \"{path}\" because it is not empty. Pass `--overwrite` to replace it.");
+        return;
+    }
+
+    if let Some(dir) = Path::new(path).parent() {
+        if let Err(e) = fs::create_dir_all(dir) {
+            eprintln!("Failed to create example directory: {e}");
+            process::exit(1);
+        }
+    }
+
+    match fs::write(path, content) {
+        Ok(()) => println!("Wrote example file \"{path}\""),
+        Err(e) => {
+            eprintln!("Failed to write example file: {e}");
+            process::exit(1);
+        }
+    }
+}
+
+fn record_answers(puzzle: PuzzleId, answers: &[String]) {
+    if answers.is_empty() {
+        println!("{ANSI_ITALIC}No answers found. Run `cargo read` after solving a part to record its answer.{ANSI_RESET}");
+        return;
+    }
+
+    let known = match Answers::read_from_file() {
+        Ok(answers) => answers,
+        Err(e) => {
+            eprintln!("Failed to read answers: {e}");
+            process::exit(1);
+        }
+    };
+
+    for (part, answer) in (1..=2).zip(answers) {
+        if known.get(puzzle, part) == Some(answer.as_str()) {
+            continue;
+        }
+
+        match Answers::record(puzzle, part, answer) {
+            Ok(()) => println!("Stored answer `{answer}` for {puzzle} part {part}."),
+            Err(e) => {
+                eprintln!("Failed to store answer: {e}");
+                process::exit(1);
+            }
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::parse_selection;
+
+    #[test]
+    fn parses_selections() {
+        assert_eq!(parse_selection("1"), Ok(vec![1]));
+        assert_eq!(parse_selection("1, 3,"), Ok(vec![1, 3]));
+        assert!(parse_selection("a").is_err());
+    }
+}
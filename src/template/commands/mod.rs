@@ -1,6 +1,10 @@
 pub mod all;
+pub mod answer;
+pub mod dhat;
 pub mod download;
+pub mod examples;
 pub mod read;
 pub mod scaffold;
 pub mod solve;
 pub mod time;
+pub mod watch;
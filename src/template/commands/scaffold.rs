@@ -1,14 +1,24 @@
 use std::{
-    fs::{File, OpenOptions},
-    io::Write,
+    fs::{self, File, OpenOptions},
+    io::{ErrorKind, Write},
+    path::Path,
     process,
 };
 
-use crate::template::Day;
+use crate::template::{config::config, extract, paths, PuzzleId};
 
 const MODULE_TEMPLATE: &str =
     include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/template.txt"));
 
+const EXPECTATIONS_TEMPLATE: &str = "\
+# Expected answers for the examples of this puzzle, checked by `cargo test`.
+# Add an `[[example]]` table for every additional example file, e.g. `%DAY%-2.txt`.
+[[example]]
+file = \"%DAY%.txt\"
+# part_1 = \"\"
+# part_2 = \"\"
+";
+
 fn safe_create_file(path: &str, overwrite: bool) -> Result<File, std::io::Error> {
     let mut file = OpenOptions::new();
     if overwrite {
@@ -19,18 +29,86 @@ fn safe_create_file(path: &str, overwrite: bool) -> Result<File, std::io::Error>
     file.truncate(true).write(true).open(path)
 }
 
-fn create_file(path: &str) -> Result<File, std::io::Error> {
-    OpenOptions::new()
-        .write(true)
-        .create(true)
-        .truncate(true)
-        .open(path)
+/// Creates a file with the given contents if it does not exist yet. Returns whether the file was created.
+fn create_missing_file(path: &str, contents: &str) -> Result<bool, std::io::Error> {
+    if let Some(dir) = Path::new(path).parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    match safe_create_file(path, false) {
+        Ok(mut file) => file.write_all(contents.as_bytes()).map(|()| true),
+        Err(e) if e.kind() == ErrorKind::AlreadyExists => Ok(false),
+        Err(e) => Err(e),
+    }
 }
 
-pub fn handle(day: Day, overwrite: bool) {
-    let input_path = format!("data/inputs/{day}.txt");
-    let example_path = format!("data/examples/{day}.txt");
-    let module_path = format!("src/bin/{day}.rs");
+/// Reads a template from the templates directory, or returns the built-in template if no name is given.
+fn read_template(name: Option<&str>) -> Result<String, String> {
+    let Some(name) = name else {
+        return Ok(MODULE_TEMPLATE.to_string());
+    };
+
+    fs::read_to_string(paths::template(name)).map_err(|_| {
+        let available = fs::read_dir(paths::templates_dir())
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|entry| {
+                let name = entry.file_name().into_string().ok()?;
+                name.strip_suffix(".txt").map(String::from)
+            })
+            .collect::<Vec<_>>();
+
+        if available.is_empty() {
+            format!("template \"{}\" does not exist.", paths::template(name))
+        } else {
+            format!(
+                "template \"{}\" does not exist. Available templates: {}.",
+                paths::template(name),
+                available.join(", ")
+            )
+        }
+    })
+}
+
+/// Replaces the placeholders of a template.
+fn render(template: &str, puzzle: PuzzleId, title: &str, answer_type: &str) -> String {
+    template
+        .replace("%YEAR%", &puzzle.year.to_string())
+        .replace("%DAY_NUMBER%", &puzzle.day.into_inner().to_string())
+        .replace("%DAY%", &puzzle.day.to_string())
+        .replace("%TITLE%", title)
+        .replace("%ANSWER_TYPE%", answer_type)
+}
+
+/// Scaffolds a solution. The template and answer type default to the `[scaffold]` section of `aoc.toml`.
+pub fn handle(
+    puzzle: PuzzleId,
+    overwrite: bool,
+    template: Option<&str>,
+    answer_type: Option<&str>,
+) {
+    let template = template.or(config().scaffold.template.as_deref());
+    let answer_type = answer_type.unwrap_or(&config().scaffold.answer_type);
+
+    let input_path = paths::input(puzzle);
+    let example_path = paths::example(puzzle);
+    let expectations_path = paths::example_expectations(puzzle);
+    let module_path = paths::bin(puzzle);
+
+    let template = match read_template(template) {
+        Ok(template) => template,
+        Err(e) => {
+            eprintln!("Failed to read template: {e}");
+            process::exit(1);
+        }
+    };
+
+    // the title is only known if the puzzle description was downloaded before.
+    let title = fs::read_to_string(paths::puzzle_description(puzzle))
+        .ok()
+        .and_then(|description| extract::title(&description))
+        .unwrap_or_else(|| format!("Day {}", puzzle.day.into_inner()));
 
     let mut file = match safe_create_file(&module_path, overwrite) {
         Ok(file) => file,
@@ -40,11 +118,7 @@ pub fn handle(day: Day, overwrite: bool) {
         }
     };
 
-    match file.write_all(
-        MODULE_TEMPLATE
-            .replace("%DAY_NUMBER%", &day.into_inner().to_string())
-            .as_bytes(),
-    ) {
+    match file.write_all(render(&template, puzzle, &title, answer_type).as_bytes()) {
         Ok(()) => {
             println!("Created module file \"{}\"", &module_path);
         }
@@ -54,26 +128,63 @@ pub fn handle(day: Day, overwrite: bool) {
         }
     }
 
-    match create_file(&input_path) {
-        Ok(_) => {
+    match create_missing_file(&input_path, "") {
+        Ok(true) => {
             println!("Created empty input file \"{}\"", &input_path);
         }
+        Ok(false) => {}
         Err(e) => {
             eprintln!("Failed to create input file: {e}");
             process::exit(1);
         }
     }
 
-    match create_file(&example_path) {
-        Ok(_) => {
+    match create_missing_file(&example_path, "") {
+        Ok(true) => {
             println!("Created empty example file \"{}\"", &example_path);
         }
+        Ok(false) => {}
         Err(e) => {
             eprintln!("Failed to create example file: {e}");
             process::exit(1);
         }
     }
 
+    match create_missing_file(
+        &expectations_path,
+        &EXPECTATIONS_TEMPLATE.replace("%DAY%", &puzzle.day.to_string()),
+    ) {
+        Ok(true) => {
+            println!("Created expectations file \"{}\"", &expectations_path);
+        }
+        Ok(false) => {}
+        Err(e) => {
+            eprintln!("Failed to create expectations file: {e}");
+            process::exit(1);
+        }
+    }
+
     println!("---");
-    println!("🎄 Type `cargo solve {day}` to run your solution.");
+    println!(
+        "🎄 Type `cargo solve {} --year {}` to run your solution.",
+        puzzle.day, puzzle.year
+    );
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::render;
+    use crate::puzzle;
+
+    #[test]
+    fn renders_templates() {
+        let template = "solution!(%YEAR%, %DAY_NUMBER%); // %DAY%: %TITLE% -> %ANSWER_TYPE%";
+
+        assert_eq!(
+            render(template, puzzle!(2024, 6), "Guard Gallivant", "u64"),
+            "solution!(2024, 6); // 06: Guard Gallivant -> u64"
+        );
+    }
 }
@@ -1,9 +1,20 @@
-use std::process::{Command, Stdio};
+use std::process::Stdio;
 
-use crate::template::Day;
+use crate::template::{
+    commands::dhat::{print_summary, DEFAULT_TOP},
+    dhat_profile::DEFAULT_PROFILE,
+    run_multi::child_commands,
+    PuzzleId,
+};
 
-pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
-    let mut cmd_args = vec!["run".to_string(), "--bin".to_string(), day.to_string()];
+pub fn handle(
+    puzzle: PuzzleId,
+    release: bool,
+    dhat: bool,
+    submit_part: Option<u8>,
+    input: Option<&str>,
+) {
+    let mut cmd_args = vec!["run".to_string(), "--bin".to_string(), puzzle.to_string()];
 
     if dhat {
         cmd_args.extend([
@@ -12,8 +23,15 @@ pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
             "--features".to_string(),
             "dhat-heap".to_string(),
         ]);
-    } else if release {
-        cmd_args.push("--release".to_string());
+    } else {
+        if release {
+            cmd_args.push("--release".to_string());
+        }
+        cmd_args.extend(
+            child_commands::feature_args()
+                .iter()
+                .map(ToString::to_string),
+        );
     }
 
     cmd_args.push("--".to_string());
@@ -23,12 +41,24 @@ pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
         cmd_args.push(submit_part.to_string());
     }
 
-    let mut cmd = Command::new("cargo")
+    if let Some(input) = input {
+        cmd_args.push("--input".to_string());
+        cmd_args.push(input.to_string());
+    }
+
+    let mut cmd = child_commands::cargo()
         .args(&cmd_args)
         .stdout(Stdio::inherit())
         .stderr(Stdio::inherit())
         .spawn()
         .unwrap();
 
-    cmd.wait().unwrap();
+    let status = cmd.wait().unwrap();
+
+    // the profile is only written if the solution exits normally.
+    if dhat && status.success() {
+        if let Err(e) = print_summary(DEFAULT_PROFILE, DEFAULT_TOP) {
+            eprintln!("Failed to summarize heap profile: {e}");
+        }
+    }
 }
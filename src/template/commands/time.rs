@@ -1,39 +1,79 @@
 use std::collections::HashSet;
+use std::process;
 
-use crate::template::run_multi::run_multi;
+use crate::template::bench::BenchConfig;
+use crate::template::limits::Limits;
+use crate::template::run_multi::{child_commands, run_multi};
+use crate::template::test_report::ReportTarget;
 use crate::template::timings::Timings;
-use crate::template::{all_days, readme_benchmarks, Day};
+use crate::template::timings_history;
+use crate::template::{all_puzzles, Day, PuzzleId, Year};
 
-pub fn handle(day: Option<Day>, run_all: bool, store: bool) {
-    let stored_timings = Timings::read_from_file();
+#[allow(clippy::too_many_arguments)]
+pub fn handle(
+    year: Year,
+    day: Option<Day>,
+    run_all: bool,
+    store: bool,
+    is_isolated: bool,
+    bench: &BenchConfig,
+    jobs: usize,
+    compare: Option<f64>,
+    limits: &Limits,
+    report: Option<&ReportTarget>,
+) {
+    // solution binaries read the benchmark config and the limits from the environment they inherit.
+    bench.export_env();
+    limits.export_env();
+
+    let stored_timings = Timings::read_from_file().unwrap_or_else(|e| {
+        eprintln!("Warning: {e}");
+        Timings::default()
+    });
 
     let days_to_run = day.map_or_else(
         || {
-            if run_all {
-                all_days().collect()
+            // comparisons need fresh timings of every day.
+            if run_all || compare.is_some() {
+                all_puzzles(year).collect()
             } else {
                 // when the `--all` flag is not set, filter out days that are fully benched.
-                all_days()
-                    .filter(|day| !stored_timings.is_day_complete(*day))
+                all_puzzles(year)
+                    .filter(|puzzle| !stored_timings.is_complete(*puzzle))
                     .collect()
             }
         },
-        |day| HashSet::from([day]),
+        |day| HashSet::from([PuzzleId::new(year, day)]),
     );
 
-    let timings = run_multi(&days_to_run, true, true).unwrap();
+    if is_isolated {
+        let run = run_multi(&days_to_run, true, true, jobs, limits);
+        let regressed = timings_history::record(run.timings.as_ref().unwrap(), store, compare);
+        let report_failed = report.is_some_and(|report| !report.write(&run.days));
 
-    if store {
-        let merged_timings = stored_timings.merge(&timings);
-        merged_timings.store_file().unwrap();
+        if regressed || run.has_failures() || report_failed {
+            process::exit(1);
+        }
+    } else {
+        let jobs = jobs.to_string();
+        let threshold = compare.map(|t| t.to_string());
 
-        println!();
-        match readme_benchmarks::update(merged_timings) {
-            Ok(()) => {
-                println!("Stored updated benchmarks.");
-            }
-            Err(_) => {
-                eprintln!("Failed to store updated benchmarks.");
+        let mut args = vec!["--time", "--jobs", &jobs];
+        if store {
+            args.push("--store");
+        }
+        if let Some(threshold) = &threshold {
+            args.extend(["--compare", threshold]);
+        }
+        let report_args = report.map(ReportTarget::args);
+        args.extend(report_args.iter().flatten().map(String::as_str));
+
+        match child_commands::run_solutions(&days_to_run, true, &args) {
+            Ok(status) if status.success() => {}
+            Ok(status) => process::exit(status.code().unwrap_or(1)),
+            Err(e) => {
+                eprintln!("Failed to run solutions: {e:?}");
+                process::exit(1);
             }
         }
     }
@@ -0,0 +1,151 @@
+/// Re-runs a solution whenever its source, example or input file changes.
+/// Changes are detected by polling modification times, so this works without platform-specific file watchers.
+use std::{fs, thread, time::Duration, time::SystemTime};
+
+use crate::template::{
+    limits::Limits,
+    paths,
+    run_multi::child_commands,
+    runner::{PartResult, PARSE_STEP},
+    PuzzleId, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET,
+};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(300);
+
+/// Files that trigger a re-run when they change.
+fn watched_files(puzzle: PuzzleId, input: Option<&str>) -> Vec<String> {
+    match input {
+        Some(input) => vec![paths::bin(puzzle), input.to_string()],
+        None => vec![
+            paths::bin(puzzle),
+            paths::example(puzzle),
+            paths::input(puzzle),
+        ],
+    }
+}
+
+fn modified_times(files: &[String]) -> Vec<Option<SystemTime>> {
+    files
+        .iter()
+        .map(|file| fs::metadata(file).and_then(|m| m.modified()).ok())
+        .collect()
+}
+
+pub fn handle(puzzle: PuzzleId, release: bool, input: Option<&str>) {
+    let files = watched_files(puzzle, input);
+    let mut previous: Vec<PartResult> = vec![];
+
+    println!(
+        "{ANSI_ITALIC}Watching {}. Press Ctrl+C to stop.{ANSI_RESET}",
+        files.join(", ")
+    );
+
+    loop {
+        let modified = modified_times(&files);
+
+        let results = run(puzzle, release, input);
+        print_changes(&previous, &results);
+
+        if !results.is_empty() {
+            previous = results;
+        }
+
+        while modified_times(&files) == modified {
+            thread::sleep(POLL_INTERVAL);
+        }
+
+        // editors may write a file in several steps, wait for them to finish.
+        thread::sleep(POLL_INTERVAL);
+
+        println!();
+        println!("{ANSI_BOLD}Change detected, re-running {puzzle}...{ANSI_RESET}");
+    }
+}
+
+/// Rebuilds and runs the solution, returning the reported results.
+fn run(puzzle: PuzzleId, release: bool, input: Option<&str>) -> Vec<PartResult> {
+    let Some(mut cmd) = child_commands::solution_command(puzzle, false, release) else {
+        eprintln!("Could not find solution {}.", paths::bin(puzzle));
+        return vec![];
+    };
+
+    if let Some(input) = input {
+        cmd.args(["--input", input]);
+    }
+
+    // the run includes the rebuild, so limits do not apply.
+    match child_commands::run_command(&mut cmd, false, &Limits::default()) {
+        Ok(run) => run.results,
+        Err(e) => {
+            eprintln!("Failed to run solution: {e:?}");
+            vec![]
+        }
+    }
+}
+
+/// Prints how the answers changed compared to the previous run.
+fn print_changes(previous: &[PartResult], results: &[PartResult]) {
+    if previous.is_empty() || results.is_empty() {
+        return;
+    }
+
+    println!("{ANSI_BOLD}Compared to the previous run:{ANSI_RESET}");
+
+    for result in results.iter().filter(|r| r.part != PARSE_STEP) {
+        let before = previous
+            .iter()
+            .find(|r| r.part == result.part)
+            .and_then(|r| r.answer.as_deref());
+
+        println!(
+            "Part {}: {}",
+            result.part,
+            describe_change(before, result.answer.as_deref())
+        );
+    }
+}
+
+fn describe_change(before: Option<&str>, after: Option<&str>) -> String {
+    let format = |answer: Option<&str>| answer.unwrap_or("✖").to_string();
+
+    if before == after {
+        format!("{} {ANSI_ITALIC}(unchanged){ANSI_RESET}", format(after))
+    } else {
+        format!(
+            "{ANSI_BOLD}{}{ANSI_RESET} {ANSI_ITALIC}(was {}){ANSI_RESET}",
+            format(after),
+            format(before)
+        )
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{describe_change, watched_files};
+    use crate::puzzle;
+
+    #[test]
+    fn watches_solution_files() {
+        assert_eq!(
+            watched_files(puzzle!(2024, 7), None),
+            vec![
+                "./src/bin/2024-07.rs",
+                "data/2024/examples/07.txt",
+                "data/2024/inputs/07.txt"
+            ]
+        );
+        assert_eq!(
+            watched_files(puzzle!(2024, 7), Some("data/2024/examples/07-2.txt")),
+            vec!["./src/bin/2024-07.rs", "data/2024/examples/07-2.txt"]
+        );
+    }
+
+    #[test]
+    fn describes_changes() {
+        assert!(describe_change(Some("42"), Some("42")).contains("unchanged"));
+        assert!(describe_change(Some("41"), Some("42")).contains("was 41"));
+        assert!(describe_change(None, Some("42")).contains("was ✖"));
+    }
+}
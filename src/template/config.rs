@@ -0,0 +1,555 @@
+/// Project configuration, read from `aoc.toml` in the project root.
+///
+/// Every setting is optional and falls back to its default. Command-line flags take precedence over the file.
+///
+/// ```toml
+/// year = 2024
+///
+/// [paths]
+/// data = "data"
+/// readme = "README.md"
+/// templates = "templates"
+/// assets = ".assets"
+///
+/// [bench]
+/// budget_ms = 1000
+/// max_samples = 10000
+/// warmup_ms = 100
+///
+/// [limits]
+/// timeout_secs = 60
+/// memory_mib = 4096
+///
+/// [scaffold]
+/// template = "grid"
+/// answer_type = "u64"
+///
+/// [submit]
+/// enabled = true
+/// check_previous = true
+///
+/// [history]
+/// machine = "laptop"
+/// regression_threshold = 10
+///
+/// [readme]
+/// columns = ["puzzle", "parse", "total", "share", "samples", "memory"]
+/// order = "slowest"
+/// highlight_slowest = 3
+/// chart = true
+/// chart_scale = "log"
+/// ```
+use std::{fs, io::ErrorKind, process, str::FromStr, sync::OnceLock, time::Duration};
+use toml::{Table, Value};
+
+use crate::template::{bench::BenchConfig, limits::Limits, Year};
+
+/// Path of the configuration file, relative to the project root.
+pub const CONFIG_FILE_PATH: &str = "aoc.toml";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PathsConfig {
+    /// Directory that holds the per-year data folders and the timings, answers and submissions files.
+    pub data: String,
+    /// Readme that contains the benchmark table.
+    pub readme: String,
+    /// Directory of the scaffold templates.
+    pub templates: String,
+    /// Directory of the benchmark charts.
+    pub assets: String,
+}
+
+impl Default for PathsConfig {
+    fn default() -> Self {
+        PathsConfig {
+            data: "data".into(),
+            readme: "README.md".into(),
+            templates: "templates".into(),
+            assets: ".assets".into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScaffoldConfig {
+    /// Template used if `--template` is not passed. Uses the built-in template if not set.
+    pub template: Option<String>,
+    /// Answer type used if `--answer-type` is not passed.
+    pub answer_type: String,
+}
+
+impl Default for ScaffoldConfig {
+    fn default() -> Self {
+        ScaffoldConfig {
+            template: None,
+            answer_type: "u32".into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SubmitConfig {
+    /// Whether answers can be submitted with `--submit`.
+    pub enabled: bool,
+    /// Whether answers that previous submissions show to be wrong are refused.
+    pub check_previous: bool,
+}
+
+impl Default for SubmitConfig {
+    fn default() -> Self {
+        SubmitConfig {
+            enabled: true,
+            check_previous: true,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct HistoryConfig {
+    /// Name of the current machine in the benchmark history. Defaults to the host name.
+    pub machine: Option<String>,
+    /// Percentage by which a part has to be slower than before to be flagged by `cargo time --compare`.
+    pub regression_threshold: f64,
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        HistoryConfig {
+            machine: None,
+            regression_threshold: 10.0,
+        }
+    }
+}
+
+/// Optional column of the readme benchmark table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BenchmarkColumn {
+    /// Link to the puzzle description.
+    Puzzle,
+    /// Duration of the parse step. Only shown if at least one solution has a parse step.
+    Parse,
+    /// Sum of the durations of a day.
+    Total,
+    /// Percentage of the total runtime of the year.
+    Share,
+    /// Number of samples of each part.
+    Samples,
+    /// Peak heap usage of each part, only recorded if the `alloc-stats` feature is enabled.
+    Memory,
+}
+
+impl FromStr for BenchmarkColumn {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "puzzle" => Ok(BenchmarkColumn::Puzzle),
+            "parse" => Ok(BenchmarkColumn::Parse),
+            "total" => Ok(BenchmarkColumn::Total),
+            "share" => Ok(BenchmarkColumn::Share),
+            "samples" => Ok(BenchmarkColumn::Samples),
+            "memory" => Ok(BenchmarkColumn::Memory),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Order of the days in the readme benchmark table.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BenchmarkOrder {
+    #[default]
+    Day,
+    Slowest,
+}
+
+/// Scale of the value axis of the benchmark chart.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ChartScale {
+    #[default]
+    Linear,
+    /// Logarithmic scale, which keeps fast parts visible next to slow ones.
+    Log,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReadmeConfig {
+    /// Optional columns of the benchmark table. Columns are always shown in the same order.
+    pub columns: Vec<BenchmarkColumn>,
+    pub order: BenchmarkOrder,
+    /// Number of slowest days of each year that are highlighted.
+    pub highlight_slowest: usize,
+    /// Whether an SVG chart of each year is rendered to the assets directory and embedded above its table.
+    pub chart: bool,
+    pub chart_scale: ChartScale,
+}
+
+impl Default for ReadmeConfig {
+    fn default() -> Self {
+        ReadmeConfig {
+            columns: vec![BenchmarkColumn::Parse],
+            order: BenchmarkOrder::Day,
+            highlight_slowest: 0,
+            chart: true,
+            chart_scale: ChartScale::Linear,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Config {
+    /// Default event year. Falls back to the `AOC_YEAR` environment variable if not set.
+    pub year: Option<Year>,
+    pub paths: PathsConfig,
+    /// Default benchmark config. The `AOC_BENCH_*` environment variables take precedence.
+    pub bench: BenchConfig,
+    /// Default limits of every day. The `AOC_TIMEOUT_SECS` and `AOC_MEMORY_LIMIT_MIB` environment variables take
+    /// precedence.
+    pub limits: Limits,
+    pub scaffold: ScaffoldConfig,
+    pub submit: SubmitConfig,
+    pub history: HistoryConfig,
+    /// Layout of the benchmark table in the readme.
+    pub readme: ReadmeConfig,
+}
+
+/// Returns the project configuration. The file is read once, a missing file results in the default config.
+/// Exits if the file is not valid.
+pub fn config() -> &'static Config {
+    static CONFIG: OnceLock<Config> = OnceLock::new();
+
+    CONFIG.get_or_init(|| match fs::read_to_string(CONFIG_FILE_PATH) {
+        Ok(s) => s.parse().unwrap_or_else(|e| {
+            eprintln!("Error: invalid {CONFIG_FILE_PATH}: {e}");
+            process::exit(1);
+        }),
+        Err(e) if e.kind() == ErrorKind::NotFound => Config::default(),
+        Err(e) => {
+            eprintln!("Error: could not read {CONFIG_FILE_PATH}: {e}");
+            process::exit(1);
+        }
+    })
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Typed access to the keys of a table that only contains known keys.
+struct Section<'a> {
+    name: &'a str,
+    table: &'a Table,
+}
+
+impl<'a> Section<'a> {
+    fn new(name: &'a str, table: &'a Table, keys: &[&str]) -> Result<Self, String> {
+        match table.keys().find(|key| !keys.contains(&key.as_str())) {
+            Some(key) => Err(format!("unknown key `{}`.", Self::path(name, key))),
+            None => Ok(Section { name, table }),
+        }
+    }
+
+    fn path(name: &str, key: &str) -> String {
+        if name.is_empty() {
+            key.to_string()
+        } else {
+            format!("{name}.{key}")
+        }
+    }
+
+    fn error(&self, key: &str, expected: &str) -> String {
+        format!(
+            "expected `{}` to be {expected}.",
+            Self::path(self.name, key)
+        )
+    }
+
+    fn section(&self, key: &'a str, keys: &[&str]) -> Result<Option<Section<'a>>, String> {
+        match self.table.get(key) {
+            None => Ok(None),
+            Some(Value::Table(table)) => Section::new(key, table, keys).map(Some),
+            Some(_) => Err(self.error(key, "a table")),
+        }
+    }
+
+    fn string(&self, key: &str) -> Result<Option<String>, String> {
+        match self.table.get(key) {
+            None => Ok(None),
+            Some(Value::String(s)) => Ok(Some(s.clone())),
+            Some(_) => Err(self.error(key, "a string")),
+        }
+    }
+
+    fn integer(&self, key: &str) -> Result<Option<u64>, String> {
+        match self.table.get(key) {
+            None => Ok(None),
+            Some(Value::Integer(n)) => u64::try_from(*n)
+                .map(Some)
+                .map_err(|_| self.error(key, "a positive integer")),
+            Some(_) => Err(self.error(key, "an integer")),
+        }
+    }
+
+    fn number(&self, key: &str) -> Result<Option<f64>, String> {
+        match self.table.get(key) {
+            None => Ok(None),
+            #[allow(clippy::cast_precision_loss)]
+            Some(Value::Integer(n)) => Ok(Some(*n as f64)),
+            Some(Value::Float(n)) => Ok(Some(*n)),
+            Some(_) => Err(self.error(key, "a number")),
+        }
+    }
+
+    fn strings(&self, key: &str) -> Result<Option<Vec<String>>, String> {
+        match self.table.get(key) {
+            None => Ok(None),
+            Some(Value::Array(values)) => values
+                .iter()
+                .map(|value| value.as_str().map(String::from))
+                .collect::<Option<_>>()
+                .map(Some)
+                .ok_or(self.error(key, "an array of strings")),
+            Some(_) => Err(self.error(key, "an array of strings")),
+        }
+    }
+
+    fn boolean(&self, key: &str) -> Result<Option<bool>, String> {
+        match self.table.get(key) {
+            None => Ok(None),
+            Some(Value::Boolean(b)) => Ok(Some(*b)),
+            Some(_) => Err(self.error(key, "a boolean")),
+        }
+    }
+}
+
+impl FromStr for Config {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let table: Table = s
+            .parse()
+            .map_err(|e: toml::de::Error| e.message().to_string())?;
+
+        let root = Section::new(
+            "",
+            &table,
+            &[
+                "year", "paths", "bench", "limits", "scaffold", "submit", "history", "readme",
+            ],
+        )?;
+
+        let mut config = Config::default();
+
+        if let Some(year) = root.integer("year")? {
+            config.year = Some(
+                u16::try_from(year)
+                    .ok()
+                    .and_then(Year::new)
+                    .ok_or(root.error("year", "a valid event year"))?,
+            );
+        }
+
+        if let Some(paths) = root.section("paths", &["data", "readme", "templates", "assets"])? {
+            let defaults = config.paths;
+            config.paths = PathsConfig {
+                data: paths.string("data")?.unwrap_or(defaults.data),
+                readme: paths.string("readme")?.unwrap_or(defaults.readme),
+                templates: paths.string("templates")?.unwrap_or(defaults.templates),
+                assets: paths.string("assets")?.unwrap_or(defaults.assets),
+            };
+        }
+
+        if let Some(bench) = root.section("bench", &["budget_ms", "max_samples", "warmup_ms"])? {
+            let defaults = config.bench;
+            config.bench = BenchConfig {
+                budget: bench
+                    .integer("budget_ms")?
+                    .map_or(defaults.budget, Duration::from_millis),
+                max_samples: bench
+                    .integer("max_samples")?
+                    .map_or(defaults.max_samples, |n| u128::from(n.max(1))),
+                warmup: bench
+                    .integer("warmup_ms")?
+                    .map_or(defaults.warmup, Duration::from_millis),
+            };
+        }
+
+        if let Some(limits) = root.section("limits", &["timeout_secs", "memory_mib"])? {
+            let defaults = config.limits;
+            config.limits = Limits::new(
+                limits
+                    .integer("timeout_secs")?
+                    .unwrap_or(defaults.timeout_secs()),
+                limits
+                    .integer("memory_mib")?
+                    .unwrap_or(defaults.memory_mib()),
+            );
+        }
+
+        if let Some(scaffold) = root.section("scaffold", &["template", "answer_type"])? {
+            config.scaffold = ScaffoldConfig {
+                template: scaffold.string("template")?,
+                answer_type: scaffold
+                    .string("answer_type")?
+                    .unwrap_or(config.scaffold.answer_type),
+            };
+        }
+
+        if let Some(submit) = root.section("submit", &["enabled", "check_previous"])? {
+            let defaults = config.submit;
+            config.submit = SubmitConfig {
+                enabled: submit.boolean("enabled")?.unwrap_or(defaults.enabled),
+                check_previous: submit
+                    .boolean("check_previous")?
+                    .unwrap_or(defaults.check_previous),
+            };
+        }
+
+        if let Some(history) = root.section("history", &["machine", "regression_threshold"])? {
+            config.history = HistoryConfig {
+                machine: history.string("machine")?,
+                regression_threshold: history
+                    .number("regression_threshold")?
+                    .unwrap_or(config.history.regression_threshold),
+            };
+        }
+
+        if let Some(readme) = root.section(
+            "readme",
+            &[
+                "columns",
+                "order",
+                "highlight_slowest",
+                "chart",
+                "chart_scale",
+            ],
+        )? {
+            let defaults = config.readme;
+            config.readme = ReadmeConfig {
+                columns: match readme.strings("columns")? {
+                    Some(columns) => columns
+                        .iter()
+                        .map(|column| column.parse())
+                        .collect::<Result<_, _>>()
+                        .map_err(|()| {
+                            readme.error(
+                                "columns",
+                                "a list of \"puzzle\", \"parse\", \"total\", \"share\", \"samples\" and \"memory\"",
+                            )
+                        })?,
+                    None => defaults.columns,
+                },
+                order: match readme.string("order")?.as_deref() {
+                    None => defaults.order,
+                    Some("day") => BenchmarkOrder::Day,
+                    Some("slowest") => BenchmarkOrder::Slowest,
+                    Some(_) => return Err(readme.error("order", "\"day\" or \"slowest\"")),
+                },
+                highlight_slowest: readme
+                    .integer("highlight_slowest")?
+                    .map_or(Ok(defaults.highlight_slowest), usize::try_from)
+                    .map_err(|_| readme.error("highlight_slowest", "a smaller integer"))?,
+                chart: readme.boolean("chart")?.unwrap_or(defaults.chart),
+                chart_scale: match readme.string("chart_scale")?.as_deref() {
+                    None => defaults.chart_scale,
+                    Some("linear") => ChartScale::Linear,
+                    Some("log") => ChartScale::Log,
+                    Some(_) => {
+                        return Err(readme.error("chart_scale", "\"linear\" or \"log\""))
+                    }
+                },
+            };
+        }
+
+        Ok(config)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::time::Duration;
+
+    use super::{BenchmarkColumn, BenchmarkOrder, Config};
+    use crate::year;
+
+    #[test]
+    fn parses_config() {
+        let config: Config = r#"
+            year = 2023
+
+            [paths]
+            data = "aoc-data"
+
+            [bench]
+            budget_ms = 500
+            max_samples = 0
+
+            [limits]
+            timeout_secs = 30
+
+            [scaffold]
+            template = "grid"
+
+            [submit]
+            enabled = false
+
+            [history]
+            regression_threshold = 2.5
+
+            [readme]
+            columns = ["total", "puzzle"]
+            order = "slowest"
+        "#
+        .parse()
+        .unwrap();
+
+        assert_eq!(config.year, Some(year!(2023)));
+        assert_eq!(config.paths.data, "aoc-data");
+        assert_eq!(config.paths.readme, "README.md");
+        assert_eq!(config.bench.budget, Duration::from_millis(500));
+        assert_eq!(config.bench.max_samples, 1);
+        assert_eq!(config.bench.warmup, Duration::from_millis(100));
+        assert_eq!(config.limits.timeout, Some(Duration::from_secs(30)));
+        assert_eq!(config.limits.memory, None);
+        assert_eq!(config.scaffold.template, Some("grid".into()));
+        assert_eq!(config.scaffold.answer_type, "u32");
+        assert!(!config.submit.enabled);
+        assert!(config.submit.check_previous);
+        assert_eq!(config.history.machine, None);
+        assert!((config.history.regression_threshold - 2.5).abs() < f64::EPSILON);
+        assert_eq!(
+            config.readme.columns,
+            vec![BenchmarkColumn::Total, BenchmarkColumn::Puzzle]
+        );
+        assert_eq!(config.readme.order, BenchmarkOrder::Slowest);
+        assert_eq!(config.readme.highlight_slowest, 0);
+    }
+
+    #[test]
+    fn handles_empty_config() {
+        assert_eq!("".parse::<Config>(), Ok(Config::default()));
+    }
+
+    #[test]
+    fn rejects_invalid_config() {
+        assert_eq!(
+            "yaer = 2024".parse::<Config>(),
+            Err("unknown key `yaer`.".into())
+        );
+        assert_eq!(
+            "[bench]\nbudget = 10".parse::<Config>(),
+            Err("unknown key `bench.budget`.".into())
+        );
+        assert_eq!(
+            "year = 1999".parse::<Config>(),
+            Err("expected `year` to be a valid event year.".into())
+        );
+        assert_eq!(
+            "[submit]\nenabled = \"yes\"".parse::<Config>(),
+            Err("expected `submit.enabled` to be a boolean.".into())
+        );
+        assert_eq!(
+            "[readme]\norder = \"fastest\"".parse::<Config>(),
+            Err("expected `readme.order` to be \"day\" or \"slowest\".".into())
+        );
+    }
+}
@@ -0,0 +1,442 @@
+/// Reads the heap profiles that `dhat` writes for `cargo solve <day> --dhat`.
+///
+/// A profile lists the allocation sites ("program points") of a run together with their backtraces. Frames are
+/// stored without their instruction addresses, so the sites of two builds of a solution can be matched up.
+use std::{
+    cmp::Reverse,
+    collections::HashMap,
+    fs,
+    ops::{Add, AddAssign},
+};
+use tinyjson::JsonValue;
+
+/// The file that `dhat` writes to the working directory.
+pub const DEFAULT_PROFILE: &str = "dhat-heap.json";
+
+/// The version of the `dhat` file format that can be read.
+const SUPPORTED_VERSION: f64 = 2.0;
+
+/// Symbol prefixes of frames that do not belong to the profiled solution.
+const LIBRARY_PREFIXES: [&str; 9] = [
+    "alloc::",
+    "core::",
+    "std::",
+    "hashbrown::",
+    "dhat::",
+    "__rust",
+    "advent_of_code::template::alloc_stats::",
+    "advent_of_code::template::runner::",
+    "advent_of_code::template::bench::",
+];
+
+/// Bytes and blocks (allocations) on the heap.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Usage {
+    pub bytes: u64,
+    pub blocks: u64,
+}
+
+impl Add for Usage {
+    type Output = Usage;
+
+    fn add(self, other: Usage) -> Usage {
+        Usage {
+            bytes: self.bytes + other.bytes,
+            blocks: self.blocks + other.blocks,
+        }
+    }
+}
+
+impl AddAssign for Usage {
+    fn add_assign(&mut self, other: Usage) {
+        *self = *self + other;
+    }
+}
+
+/// An allocation site and the allocations it made.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Site {
+    /// Allocations over the entire run.
+    pub total: Usage,
+    /// Allocations that were live when the heap usage peaked.
+    pub peak: Usage,
+    /// Allocations that were not freed at the end of the run.
+    pub end: Usage,
+    /// Backtrace of the allocations, innermost frame first, e.g. `day_01::part_one (src/bin/2024-01.rs:10:5)`.
+    pub frames: Vec<String>,
+}
+
+/// The allocations of a site in two profiles.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SiteDiff {
+    pub frames: Vec<String>,
+    pub before: Usage,
+    pub after: Usage,
+}
+
+impl SiteDiff {
+    #[allow(clippy::cast_possible_wrap)]
+    pub fn bytes_change(&self) -> i64 {
+        self.after.bytes as i64 - self.before.bytes as i64
+    }
+
+    #[allow(clippy::cast_possible_wrap)]
+    pub fn blocks_change(&self) -> i64 {
+        self.after.blocks as i64 - self.before.blocks as i64
+    }
+}
+
+/// Represents a `dhat` heap profile.
+/// Can be serialized from JSON.
+#[derive(Clone, Debug, Default)]
+pub struct Profile {
+    /// Allocation sites, with sites that have the same backtrace merged into one.
+    pub sites: Vec<Site>,
+}
+
+impl Profile {
+    pub fn read_from_file(path: &str) -> Result<Self, String> {
+        let content =
+            fs::read_to_string(path).map_err(|e| format!("could not read {path}: {e}"))?;
+        Profile::try_from(content).map_err(|e| format!("could not parse {path}: {e}"))
+    }
+
+    pub fn total(&self) -> Usage {
+        self.sum(|site| site.total)
+    }
+
+    pub fn peak(&self) -> Usage {
+        self.sum(|site| site.peak)
+    }
+
+    pub fn end(&self) -> Usage {
+        self.sum(|site| site.end)
+    }
+
+    fn sum(&self, usage: impl Fn(&Site) -> Usage) -> Usage {
+        self.sites
+            .iter()
+            .fold(Usage::default(), |sum, site| sum + usage(site))
+    }
+
+    /// Returns the `limit` sites that allocated the most bytes over the run.
+    pub fn top_by_bytes(&self, limit: usize) -> Vec<&Site> {
+        self.top_by(limit, |site| site.total.bytes)
+    }
+
+    /// Returns the `limit` sites that made the most allocations over the run.
+    pub fn top_by_blocks(&self, limit: usize) -> Vec<&Site> {
+        self.top_by(limit, |site| site.total.blocks)
+    }
+
+    fn top_by(&self, limit: usize, key: impl Fn(&Site) -> u64) -> Vec<&Site> {
+        let mut sites: Vec<&Site> = self.sites.iter().filter(|site| key(site) > 0).collect();
+        sites.sort_by_key(|site| (Reverse(key(site)), &site.frames));
+        sites.truncate(limit);
+        sites
+    }
+
+    /// Matches the sites of two profiles by their backtraces and returns the sites that changed,
+    /// largest change in bytes first.
+    pub fn diff(&self, after: &Profile) -> Vec<SiteDiff> {
+        let mut diffs: Vec<SiteDiff> = Vec::new();
+        let mut indices: HashMap<&[String], usize> = HashMap::new();
+
+        for (site, is_after) in self
+            .sites
+            .iter()
+            .map(|site| (site, false))
+            .chain(after.sites.iter().map(|site| (site, true)))
+        {
+            let index = *indices.entry(site.frames.as_slice()).or_insert_with(|| {
+                diffs.push(SiteDiff {
+                    frames: site.frames.clone(),
+                    before: Usage::default(),
+                    after: Usage::default(),
+                });
+                diffs.len() - 1
+            });
+
+            if is_after {
+                diffs[index].after += site.total;
+            } else {
+                diffs[index].before += site.total;
+            }
+        }
+
+        diffs.retain(|diff| diff.before != diff.after);
+        diffs.sort_by_key(|diff| {
+            (
+                Reverse(diff.bytes_change().unsigned_abs()),
+                Reverse(diff.blocks_change().unsigned_abs()),
+                diff.frames.clone(),
+            )
+        });
+        diffs
+    }
+}
+
+/// Returns up to `limit` frames of a backtrace that belong to the solution, skipping frames of the standard
+/// library and of the runner. Falls back to the innermost frame if no frame belongs to the solution.
+pub fn solution_frames(frames: &[String], limit: usize) -> Vec<&str> {
+    let solution: Vec<&str> = frames
+        .iter()
+        .map(String::as_str)
+        .filter(|frame| {
+            let symbol = frame.trim_start_matches('<');
+            !LIBRARY_PREFIXES
+                .iter()
+                .any(|prefix| symbol.starts_with(prefix))
+        })
+        .take(limit)
+        .collect();
+
+    if solution.is_empty() {
+        frames.iter().map(String::as_str).take(1).collect()
+    } else {
+        solution
+    }
+}
+
+/// Strips the instruction address from a frame, e.g. `0x10c0a1b4e: day_01::part_one (...)`.
+fn strip_address(frame: &str) -> &str {
+    match frame.split_once(": ") {
+        Some((address, symbol)) if address.starts_with("0x") => symbol,
+        _ => frame,
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl TryFrom<String> for Profile {
+    type Error = String;
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let json = value.parse::<JsonValue>().or(Err("not valid JSON file."))?;
+
+        let json = json
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("expected profile to be a JSON object.")?;
+
+        match json.get("dhatFileVersion").and_then(|v| v.get::<f64>()) {
+            Some(version) if *version == SUPPORTED_VERSION => {}
+            _ => return Err("unsupported dhat file version, expected version 2.".into()),
+        }
+
+        if json
+            .get("mode")
+            .and_then(|v| v.get::<String>())
+            .map(String::as_str)
+            != Some("rust-heap")
+        {
+            return Err("expected a heap profile.".into());
+        }
+
+        let frame_table: Vec<&str> = json
+            .get("ftbl")
+            .and_then(|v| v.get::<Vec<JsonValue>>())
+            .ok_or("expected ftbl to be an array.")?
+            .iter()
+            .map(|frame| frame.get::<String>().map(|frame| strip_address(frame)))
+            .collect::<Option<_>>()
+            .ok_or("expected ftbl to only contain strings.")?;
+
+        let program_points = json
+            .get("pps")
+            .and_then(|v| v.get::<Vec<JsonValue>>())
+            .ok_or("expected pps to be an array.")?;
+
+        let mut sites: Vec<Site> = Vec::new();
+        let mut indices: HashMap<Vec<String>, usize> = HashMap::new();
+
+        for point in program_points {
+            let point = point
+                .get::<HashMap<String, JsonValue>>()
+                .ok_or("expected pps to contain objects.")?;
+
+            let number = |key: &str| {
+                point
+                    .get(key)
+                    .and_then(|v| v.get::<f64>())
+                    .map(|x| *x as u64)
+                    .ok_or(format!("expected pps.{key} to be a number."))
+            };
+
+            let usage = |bytes: &str, blocks: &str| -> Result<Usage, String> {
+                Ok(Usage {
+                    bytes: number(bytes)?,
+                    blocks: number(blocks)?,
+                })
+            };
+
+            let frames = point
+                .get("fs")
+                .and_then(|v| v.get::<Vec<JsonValue>>())
+                .ok_or("expected pps.fs to be an array.")?
+                .iter()
+                .map(|index| {
+                    index
+                        .get::<f64>()
+                        .and_then(|index| frame_table.get(*index as usize))
+                        .map(|frame| (*frame).to_string())
+                })
+                .collect::<Option<Vec<String>>>()
+                .ok_or("expected pps.fs to contain indices of ftbl.")?;
+
+            let site = Site {
+                total: usage("tb", "tbk")?,
+                peak: usage("gb", "gbk")?,
+                end: usage("eb", "ebk")?,
+                frames,
+            };
+
+            // inlined frames share an address, so the same backtrace can be recorded more than once.
+            match indices.get(&site.frames) {
+                Some(index) => {
+                    let existing = &mut sites[*index];
+                    existing.total += site.total;
+                    existing.peak += site.peak;
+                    existing.end += site.end;
+                }
+                None => {
+                    indices.insert(site.frames.clone(), sites.len());
+                    sites.push(site);
+                }
+            }
+        }
+
+        Ok(Profile { sites })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{solution_frames, Profile, Usage};
+
+    fn profile(sites: &str) -> Profile {
+        Profile::try_from(format!(
+            r#"{{
+                "dhatFileVersion": 2,
+                "mode": "rust-heap",
+                "verb": "Allocated",
+                "pps": [{sites}],
+                "ftbl": [
+                    "[root]",
+                    "0x1a0: alloc::vec::from_elem (alloc/src/vec/mod.rs:2680:5)",
+                    "0x1b0: day_01::part_one (src/bin/2024-01.rs:10:5)",
+                    "0x1c0: day_01::parse (src/bin/2024-01.rs:4:9)",
+                    "0x1d0: advent_of_code::template::runner::run_timed (src/template/runner.rs:219:9)",
+                    "0x1e0: day_01::part_one (src/bin/2024-01.rs:10:5)"
+                ]
+            }}"#
+        ))
+        .unwrap()
+    }
+
+    fn site(total: (u64, u64), peak: (u64, u64), frames: &str) -> String {
+        format!(
+            r#"{{"tb": {}, "tbk": {}, "mb": 0, "mbk": 0, "gb": {}, "gbk": {}, "eb": 0, "ebk": 0, "fs": [{frames}]}}"#,
+            total.0, total.1, peak.0, peak.1
+        )
+    }
+
+    #[test]
+    fn reads_profile() {
+        let profile = profile(
+            &[
+                site((4096, 1), (4096, 1), "1, 2, 4"),
+                site((300, 30), (10, 1), "3, 4"),
+                // same backtrace as the first site at a different address.
+                site((1024, 1), (0, 0), "1, 5, 4"),
+            ]
+            .join(","),
+        );
+
+        assert_eq!(profile.sites.len(), 2);
+        assert_eq!(
+            profile.total(),
+            Usage {
+                bytes: 5420,
+                blocks: 32
+            }
+        );
+        assert_eq!(
+            profile.peak(),
+            Usage {
+                bytes: 4106,
+                blocks: 2
+            }
+        );
+        assert_eq!(
+            profile.sites[0].frames,
+            vec![
+                "alloc::vec::from_elem (alloc/src/vec/mod.rs:2680:5)",
+                "day_01::part_one (src/bin/2024-01.rs:10:5)",
+                "advent_of_code::template::runner::run_timed (src/template/runner.rs:219:9)",
+            ]
+        );
+
+        let by_bytes = profile.top_by_bytes(1);
+        assert_eq!(by_bytes.len(), 1);
+        assert_eq!(by_bytes[0].total.bytes, 5120);
+        assert_eq!(profile.top_by_blocks(5)[0].total.blocks, 30);
+    }
+
+    #[test]
+    fn shows_solution_frames() {
+        let site_profile = profile(&site((8, 1), (8, 1), "1, 2, 3, 4"));
+        assert_eq!(
+            solution_frames(&site_profile.sites[0].frames, 3),
+            vec![
+                "day_01::part_one (src/bin/2024-01.rs:10:5)",
+                "day_01::parse (src/bin/2024-01.rs:4:9)",
+            ]
+        );
+
+        let site_profile = profile(&site((8, 1), (8, 1), "1, 4"));
+        assert_eq!(
+            solution_frames(&site_profile.sites[0].frames, 3),
+            vec!["alloc::vec::from_elem (alloc/src/vec/mod.rs:2680:5)"]
+        );
+    }
+
+    #[test]
+    fn diffs_profiles() {
+        let before = profile(
+            &[
+                site((4096, 1), (4096, 1), "1, 2"),
+                site((300, 30), (10, 1), "3"),
+                site((64, 2), (64, 2), "4"),
+            ]
+            .join(","),
+        );
+        let after = profile(
+            &[
+                site((4096, 1), (4096, 1), "1, 5"),
+                site((100, 10), (10, 1), "3"),
+                site((16, 1), (16, 1), "1"),
+            ]
+            .join(","),
+        );
+
+        let diff = before.diff(&after);
+        let changes: Vec<_> = diff
+            .iter()
+            .map(|site| (site.bytes_change(), site.blocks_change()))
+            .collect();
+
+        // the unchanged site is matched up by its frames and skipped.
+        assert_eq!(changes, vec![(-200, -20), (-64, -2), (16, 1)]);
+    }
+
+    #[test]
+    fn rejects_ad_hoc_profiles() {
+        let result = Profile::try_from(
+            r#"{"dhatFileVersion": 2, "mode": "rust-ad-hoc", "pps": [], "ftbl": []}"#.to_string(),
+        );
+        assert_eq!(result.unwrap_err(), "expected a heap profile.");
+    }
+}
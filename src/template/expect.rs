@@ -0,0 +1,279 @@
+/// Checks solution parts against the expected answers of their example files.
+///
+/// Expectations are stored next to the examples of a puzzle, e.g. in `data/2024/examples/06.expect.toml`:
+///
+/// ```toml
+/// [[example]]
+/// file = "06.txt"
+/// part_1 = 41
+/// part_2 = 6
+///
+/// [[example]]
+/// file = "06-2.txt"
+/// part_2 = "abc"
+/// ```
+use std::{
+    fmt::Display,
+    fs, io,
+    panic::{self, AssertUnwindSafe},
+    str::FromStr,
+};
+use toml::{Table, Value};
+
+use crate::template::{
+    paths,
+    runner::{panic_message, PartOutput},
+    PuzzleId, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET,
+};
+
+/// An example file and the answers expected for it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Example {
+    /// Name of the example file, relative to the examples directory.
+    pub file: String,
+    pub part_1: Option<String>,
+    pub part_2: Option<String>,
+}
+
+impl Example {
+    /// Returns the expected answer for a part, if there is one.
+    pub fn expected(&self, part: u8) -> Option<&str> {
+        match part {
+            1 => self.part_1.as_deref(),
+            2 => self.part_2.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ExpectationsError {
+    IO(io::Error),
+    Parse(String),
+}
+
+impl Display for ExpectationsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExpectationsError::IO(e) => write!(f, "{e}"),
+            ExpectationsError::Parse(e) => write!(f, "invalid expectations: {e}"),
+        }
+    }
+}
+
+/// The expected answers for the examples of a puzzle.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Expectations {
+    pub examples: Vec<Example>,
+}
+
+impl Expectations {
+    /// Reads the expectations file of a puzzle.
+    pub fn read_from_file(puzzle: PuzzleId) -> Result<Self, ExpectationsError> {
+        fs::read_to_string(paths::example_expectations(puzzle))
+            .map_err(ExpectationsError::IO)?
+            .parse()
+            .map_err(ExpectationsError::Parse)
+    }
+}
+
+impl FromStr for Expectations {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let table: Table = s
+            .parse()
+            .map_err(|e: toml::de::Error| e.message().to_string())?;
+
+        let examples = match table.get("example") {
+            Some(Value::Array(examples)) => examples,
+            Some(_) => return Err("Expected `example` to be an array of tables.".into()),
+            None => return Ok(Expectations::default()),
+        };
+
+        let examples = examples
+            .iter()
+            .enumerate()
+            .map(|(i, example)| {
+                parse_example(example).map_err(|e| format!("example {}: {e}", i + 1))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Expectations { examples })
+    }
+}
+
+fn parse_example(value: &Value) -> Result<Example, String> {
+    let table = value.as_table().ok_or("Expected a table.")?;
+
+    // answers can be written as numbers for convenience.
+    let answer = |key: &str| match table.get(key) {
+        None => Ok(None),
+        Some(Value::String(s)) => Ok(Some(s.clone())),
+        Some(Value::Integer(n)) => Ok(Some(n.to_string())),
+        Some(_) => Err(format!("Expected `{key}` to be a string or an integer.")),
+    };
+
+    if let Some(key) = table
+        .keys()
+        .find(|key| !["file", "part_1", "part_2"].contains(&key.as_str()))
+    {
+        return Err(format!("Unknown key `{key}`."));
+    }
+
+    Ok(Example {
+        file: table
+            .get("file")
+            .and_then(Value::as_str)
+            .ok_or("Expected `file` to be a string.")?
+            .to_string(),
+        part_1: answer("part_1")?,
+        part_2: answer("part_2")?,
+    })
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Runs a part against every example that has an expected answer for it and prints the outcome per example.
+/// Panics with a summary of the failed examples, so this can be used as the body of a test.
+#[track_caller]
+pub fn check<O: PartOutput>(puzzle: PuzzleId, part: u8, func: impl Fn(&str) -> O) {
+    let path = paths::example_expectations(puzzle);
+
+    let expectations = match Expectations::read_from_file(puzzle) {
+        Ok(expectations) => expectations,
+        Err(e) => panic!("Could not read {path}: {e}"),
+    };
+
+    let mut checked = 0;
+    let mut failures: Vec<String> = vec![];
+
+    for example in &expectations.examples {
+        let Some(expected) = example.expected(part) else {
+            continue;
+        };
+
+        checked += 1;
+
+        let outcome = fs::read_to_string(example_path(puzzle, example))
+            .map_err(|e| format!("could not open file: {e}"))
+            .and_then(|input| {
+                let answer = panic::catch_unwind(AssertUnwindSafe(|| func(&input).answer()))
+                    .unwrap_or_else(|e| Err(format!("panicked: {}", panic_message(&*e))));
+                compare(expected, &answer)
+            });
+
+        match outcome {
+            Ok(()) => println!("✔ {}: {expected}", example.file),
+            Err(e) => {
+                println!("✖ {}: {ANSI_BOLD}{e}{ANSI_RESET}", example.file);
+                failures.push(example.file.clone());
+            }
+        }
+    }
+
+    if checked == 0 {
+        println!("{ANSI_ITALIC}No expected answers for part {part} in {path}.{ANSI_RESET}");
+    }
+
+    assert!(
+        failures.is_empty(),
+        "Part {part} failed for {} of {checked} example(s): {}",
+        failures.len(),
+        failures.join(", ")
+    );
+}
+
+fn example_path(puzzle: PuzzleId, example: &Example) -> String {
+    format!(
+        "{}/{}",
+        paths::data_dir(puzzle.year, "examples"),
+        example.file
+    )
+}
+
+/// Compares the answer of a part against the expected answer and describes a mismatch.
+fn compare(expected: &str, answer: &Result<Option<String>, String>) -> Result<(), String> {
+    match answer {
+        Ok(Some(answer)) if answer.trim() == expected => Ok(()),
+        Ok(Some(answer)) => Err(format!("expected {expected}, got {answer}")),
+        Ok(None) => Err(format!("expected {expected}, got no answer")),
+        Err(e) => Err(format!("expected {expected}, got error: {e}")),
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{compare, Example, Expectations};
+
+    #[test]
+    fn parses_expectations() {
+        let expectations: Expectations = r#"
+            [[example]]
+            file = "06.txt"
+            part_1 = 41
+            part_2 = "6"
+
+            [[example]]
+            file = "06-2.txt"
+            part_2 = "abc"
+        "#
+        .parse()
+        .unwrap();
+
+        assert_eq!(
+            expectations.examples,
+            vec![
+                Example {
+                    file: "06.txt".into(),
+                    part_1: Some("41".into()),
+                    part_2: Some("6".into()),
+                },
+                Example {
+                    file: "06-2.txt".into(),
+                    part_1: None,
+                    part_2: Some("abc".into()),
+                }
+            ]
+        );
+
+        assert_eq!(expectations.examples[1].expected(1), None);
+        assert_eq!(expectations.examples[1].expected(2), Some("abc"));
+    }
+
+    #[test]
+    fn handles_empty_expectations() {
+        let expectations: Expectations = "# nothing here yet".parse().unwrap();
+        assert!(expectations.examples.is_empty());
+    }
+
+    #[test]
+    fn rejects_invalid_expectations() {
+        assert!("[[example]]\npart_1 = 1".parse::<Expectations>().is_err());
+        assert!("[[example]]\nfile = \"06.txt\"\npart_one = 1"
+            .parse::<Expectations>()
+            .is_err());
+        assert!("[[example]]\nfile = \"06.txt\"\npart_1 = 1.5"
+            .parse::<Expectations>()
+            .is_err());
+    }
+
+    #[test]
+    fn compares_answers() {
+        assert_eq!(compare("41", &Ok(Some("41".into()))), Ok(()));
+        assert_eq!(
+            compare("41", &Ok(Some("40".into()))),
+            Err("expected 41, got 40".into())
+        );
+        assert_eq!(
+            compare("41", &Ok(None)),
+            Err("expected 41, got no answer".into())
+        );
+        assert_eq!(
+            compare("41", &Err("boom".into())),
+            Err("expected 41, got error: boom".into())
+        );
+    }
+}
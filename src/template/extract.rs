@@ -0,0 +1,150 @@
+/// Extracts examples and answers from downloaded puzzle descriptions.
+///
+/// Descriptions are read as markdown, as written by `cargo download`. HTML puzzle pages are converted
+/// to markdown first, so both formats are supported.
+use crate::template::html;
+
+/// A code block of a puzzle description.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CodeBlock {
+    pub content: String,
+    /// Whether the paragraph before the block mentions an example, e.g. "For example:".
+    pub is_example: bool,
+}
+
+/// Returns the code blocks of a puzzle description in order of appearance.
+#[must_use]
+pub fn code_blocks(description: &str) -> Vec<CodeBlock> {
+    let markdown = to_markdown(description);
+
+    let mut blocks = vec![];
+    let mut current: Option<String> = None;
+    let mut paragraph = String::new();
+
+    for line in markdown.lines() {
+        match current.as_mut() {
+            Some(content) if line == "```" => {
+                blocks.push(CodeBlock {
+                    content: std::mem::take(content),
+                    is_example: paragraph.to_lowercase().contains("example"),
+                });
+                current = None;
+                paragraph.clear();
+            }
+            Some(content) => {
+                content.push_str(line);
+                content.push('\n');
+            }
+            None if line == "```" => current = Some(String::new()),
+            None if !line.trim().is_empty() => paragraph = line.to_string(),
+            None => {}
+        }
+    }
+
+    blocks
+}
+
+/// Returns the answers of the solved parts of a puzzle, in order of the parts.
+#[must_use]
+pub fn answers(description: &str) -> Vec<String> {
+    to_markdown(description)
+        .lines()
+        .filter_map(|line| line.strip_prefix("Your puzzle answer was `"))
+        .filter_map(|rest| rest.split_once('`'))
+        .map(|(answer, _)| answer.to_string())
+        .collect()
+}
+
+/// Returns the title of a puzzle, e.g. `Historian Hysteria` for `## --- Day 1: Historian Hysteria ---`.
+#[must_use]
+pub fn title(description: &str) -> Option<String> {
+    to_markdown(description)
+        .lines()
+        .find_map(|line| line.strip_prefix("## --- Day "))
+        .and_then(|heading| heading.split_once(": "))
+        .map(|(_, title)| title.trim_end_matches(" ---").trim().to_string())
+}
+
+fn to_markdown(description: &str) -> String {
+    if description.trim_start().starts_with('<') {
+        html::to_markdown(description)
+    } else {
+        description.to_string()
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{answers, code_blocks, title, CodeBlock};
+
+    const DESCRIPTION: &str = "## --- Day 1: Historian Hysteria ---
+
+The lists are written like this:
+
+```
+a b
+```
+
+For example:
+
+```
+3   4
+4   3
+```
+
+In the example above, the total is `11`.
+
+## --- Part Two ---
+
+Your puzzle answer was `1234`.
+
+Your puzzle answer was `abc`.
+";
+
+    #[test]
+    fn extracts_code_blocks() {
+        assert_eq!(
+            code_blocks(DESCRIPTION),
+            vec![
+                CodeBlock {
+                    content: "a b\n".into(),
+                    is_example: false,
+                },
+                CodeBlock {
+                    content: "3   4\n4   3\n".into(),
+                    is_example: true,
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn extracts_titles() {
+        assert_eq!(title(DESCRIPTION), Some("Historian Hysteria".into()));
+        assert_eq!(title("No title"), None);
+    }
+
+    #[test]
+    fn extracts_answers() {
+        assert_eq!(answers(DESCRIPTION), vec!["1234", "abc"]);
+        assert!(answers("## --- Day 1 ---\n\nNo answers yet.").is_empty());
+    }
+
+    #[test]
+    fn extracts_from_html() {
+        let page =
+            "<main><article><p>For example:</p><pre><code>1\n<em>2</em>\n</code></pre></article>\
+            <p>Your puzzle answer was <code>42</code>.</p></main>";
+
+        assert_eq!(
+            code_blocks(page),
+            vec![CodeBlock {
+                content: "1\n2\n".into(),
+                is_example: true,
+            }]
+        );
+        assert_eq!(answers(page), vec!["42"]);
+    }
+}
@@ -0,0 +1,283 @@
+//! Minimal conversion of advent of code puzzle pages to markdown.
+//! Only the tags that are used in puzzle descriptions are handled, everything else is stripped.
+
+/// Converts the puzzle descriptions contained in an advent of code puzzle page to markdown.
+/// Only `<article>` elements and the "Your puzzle answer was" paragraphs are kept.
+#[must_use]
+pub fn to_markdown(html: &str) -> String {
+    let main = html.split_once("<main>").map_or(html, |(_, rest)| {
+        rest.split("</main>").next().unwrap_or(rest)
+    });
+
+    let mut writer = MarkdownWriter::default();
+    let mut depth_article = 0;
+    let mut answer_paragraph = false;
+
+    for token in tokenize(main) {
+        match &token {
+            Token::Open(name, _) if name == "article" => {
+                depth_article += 1;
+                writer.in_article = true;
+            }
+            Token::Close(name) if name == "article" => {
+                depth_article -= 1;
+                writer.in_article = depth_article > 0;
+                writer.block_end();
+            }
+            Token::Open(name, _) if name == "p" && depth_article == 0 => {
+                answer_paragraph = true;
+                writer.handle(&token);
+            }
+            Token::Close(name) if name == "p" && answer_paragraph => {
+                answer_paragraph = false;
+                writer.handle(&token);
+            }
+            _ if depth_article > 0 || answer_paragraph => writer.handle(&token),
+            _ => {}
+        }
+    }
+
+    writer
+        .finish()
+        .iter()
+        // drop paragraphs outside of articles that are not answer paragraphs.
+        .filter(|block| !block.starts_with(OUTSIDE_MARKER) || block.contains("puzzle answer was"))
+        .map(|block| block.trim_start_matches(OUTSIDE_MARKER))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+        + "\n"
+}
+
+/// Decodes the HTML entities used on advent of code pages.
+#[must_use]
+pub fn decode_entities(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&apos;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+/// Strips all tags from an HTML fragment and decodes its entities.
+#[must_use]
+pub fn strip_tags(html: &str) -> String {
+    let text: String = tokenize(html)
+        .into_iter()
+        .filter_map(|token| match token {
+            Token::Text(text) => Some(text),
+            _ => None,
+        })
+        .collect();
+    decode_entities(&text)
+}
+
+/* -------------------------------------------------------------------------- */
+
+// paragraphs outside of articles are prefixed with this marker so they can be filtered.
+const OUTSIDE_MARKER: &str = "\u{0}";
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Open(String, Option<String>),
+    Close(String),
+    Text(String),
+}
+
+fn tokenize(html: &str) -> Vec<Token> {
+    let mut tokens = vec![];
+    let mut rest = html;
+
+    while let Some(start) = rest.find('<') {
+        if start > 0 {
+            tokens.push(Token::Text(rest[..start].to_string()));
+        }
+
+        let Some(end) = rest[start..].find('>') else {
+            rest = &rest[start..];
+            break;
+        };
+
+        let tag = &rest[start + 1..start + end];
+        rest = &rest[start + end + 1..];
+
+        if let Some(name) = tag.strip_prefix('/') {
+            tokens.push(Token::Close(name.trim().to_lowercase()));
+        } else if !tag.starts_with('!') {
+            let name = tag
+                .split(|c: char| c.is_whitespace() || c == '/')
+                .next()
+                .unwrap_or_default()
+                .to_lowercase();
+            tokens.push(Token::Open(name, parse_href(tag)));
+        }
+    }
+
+    if !rest.is_empty() {
+        tokens.push(Token::Text(rest.to_string()));
+    }
+
+    tokens
+}
+
+fn parse_href(tag: &str) -> Option<String> {
+    let (_, rest) = tag.split_once("href=\"")?;
+    Some(decode_entities(rest.split('"').next()?))
+}
+
+/// Writes markdown block by block, so blank lines inside `<pre>` blocks are kept verbatim.
+#[derive(Default)]
+struct MarkdownWriter {
+    blocks: Vec<String>,
+    out: String,
+    in_pre: bool,
+    in_article: bool,
+    links: Vec<Option<String>>,
+}
+
+impl MarkdownWriter {
+    fn handle(&mut self, token: &Token) {
+        match token {
+            Token::Text(text) => {
+                let text = decode_entities(text);
+                if self.in_pre {
+                    self.out.push_str(&text);
+                } else {
+                    let text = text.replace('\n', " ");
+                    // skip whitespace between block elements.
+                    if !(self.out.is_empty() || self.out.ends_with('\n')) || !text.trim().is_empty()
+                    {
+                        self.out.push_str(&text);
+                    }
+                }
+            }
+            Token::Open(name, href) => match name.as_str() {
+                "h2" => {
+                    self.block_end();
+                    self.out.push_str("## ");
+                }
+                "p" => {
+                    self.block_end();
+                    if !self.in_article {
+                        self.out.push_str(OUTSIDE_MARKER);
+                    }
+                }
+                "pre" => {
+                    self.block_end();
+                    self.in_pre = true;
+                    self.out.push_str("```\n");
+                }
+                "ul" => self.block_end(),
+                "li" => {
+                    if !self.out.is_empty() {
+                        self.out.push('\n');
+                    }
+                    self.out.push_str("- ");
+                }
+                "code" if !self.in_pre => self.out.push('`'),
+                "em" if !self.in_pre => self.out.push('*'),
+                "a" => {
+                    self.out.push('[');
+                    self.links.push(href.clone());
+                }
+                _ => {}
+            },
+            Token::Close(name) => match name.as_str() {
+                "h2" | "p" | "ul" => self.block_end(),
+                "pre" => {
+                    self.in_pre = false;
+                    if !self.out.ends_with('\n') {
+                        self.out.push('\n');
+                    }
+                    self.out.push_str("```");
+                    self.block_end();
+                }
+                "code" if !self.in_pre => self.out.push('`'),
+                "em" if !self.in_pre => self.out.push('*'),
+                "a" => match self.links.pop().flatten() {
+                    Some(href) => self.out.push_str(&format!("]({href})")),
+                    None => self.out.push(']'),
+                },
+                _ => {}
+            },
+        }
+    }
+
+    fn block_end(&mut self) {
+        let trimmed = self.out.trim_end_matches([' ', '\n']).len();
+        self.out.truncate(trimmed);
+        if !self.out.is_empty() {
+            self.blocks.push(std::mem::take(&mut self.out));
+        }
+    }
+
+    fn finish(mut self) -> Vec<String> {
+        self.block_end();
+        self.blocks
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{strip_tags, to_markdown};
+
+    const PAGE: &str = r#"<html><body><header>nav</header><main>
+<article class="day-desc"><h2>--- Day 1: Historian Hysteria ---</h2><p>The <em>Chief Historian</em> is always present.</p>
+<p>For example:</p>
+<pre><code>3   4
+4   3
+</code></pre>
+<pre><code>#.#
+
+
+..#
+</code></pre>
+<ul><li>The smallest number is <code>1</code>.</li><li>See <a href="/2024/about">about</a>.</li></ul>
+</article>
+<p>Your puzzle answer was <code>1234</code>.</p>
+<form method="post"><p>Answer: <input type="text" name="answer"/></p></form>
+</main></body></html>"#;
+
+    #[test]
+    fn converts_puzzle_pages() {
+        let expected = [
+            "## --- Day 1: Historian Hysteria ---",
+            "",
+            "The *Chief Historian* is always present.",
+            "",
+            "For example:",
+            "",
+            "```",
+            "3   4",
+            "4   3",
+            "```",
+            "",
+            "```",
+            "#.#",
+            "",
+            "",
+            "..#",
+            "```",
+            "",
+            "- The smallest number is `1`.",
+            "- See [about](/2024/about).",
+            "",
+            "Your puzzle answer was `1234`.",
+            "",
+        ]
+        .join("\n");
+
+        assert_eq!(to_markdown(PAGE), expected);
+    }
+
+    #[test]
+    fn strips_tags() {
+        assert_eq!(
+            strip_tags("<p>That's the <em>right</em> answer &amp; more</p>"),
+            "That's the right answer & more"
+        );
+    }
+}
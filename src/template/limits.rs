@@ -0,0 +1,236 @@
+/// Limits the time and memory of the child processes that run a single day.
+///
+/// The timeout is enforced by killing the child. The memory limit caps the address space of the child with
+/// `setrlimit`, so allocations beyond it fail and abort the child. Memory limits are only enforced on Linux.
+use std::{
+    env,
+    fmt::Display,
+    process::{Command, ExitStatus},
+    str::FromStr,
+    time::Duration,
+};
+
+use crate::template::{alloc_stats::format_bytes, config::config};
+
+/// Time and memory a day may use. A limit of `None` is not enforced.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Limits {
+    /// Wall-clock time of a day, including its benchmarks.
+    pub timeout: Option<Duration>,
+    /// Address space of a day in bytes.
+    pub memory: Option<u64>,
+}
+
+/// A limit that stopped a day.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LimitExceeded {
+    Timeout,
+    Memory,
+}
+
+impl Limits {
+    /// Creates limits from a timeout in seconds and a memory limit in MiB. A value of `0` disables a limit.
+    pub fn new(timeout_secs: u64, memory_mib: u64) -> Self {
+        Limits {
+            timeout: (timeout_secs > 0).then(|| Duration::from_secs(timeout_secs)),
+            memory: (memory_mib > 0).then(|| memory_mib.saturating_mul(1024 * 1024)),
+        }
+    }
+
+    /// Reads the limits from the `AOC_TIMEOUT_SECS` and `AOC_MEMORY_LIMIT_MIB` environment variables, falling back
+    /// to the `[limits]` section of `aoc.toml` for missing values.
+    pub fn from_env() -> Self {
+        fn var(name: &str) -> Option<u64> {
+            env::var(name).ok().and_then(|v| v.parse().ok())
+        }
+
+        let default = config().limits;
+
+        Limits::new(
+            var("AOC_TIMEOUT_SECS").unwrap_or(default.timeout_secs()),
+            var("AOC_MEMORY_LIMIT_MIB").unwrap_or(default.memory_mib()),
+        )
+    }
+
+    /// Sets the environment variables read by [`Limits::from_env`], so child processes inherit the limits.
+    pub fn export_env(&self) {
+        env::set_var("AOC_TIMEOUT_SECS", self.timeout_secs().to_string());
+        env::set_var("AOC_MEMORY_LIMIT_MIB", self.memory_mib().to_string());
+    }
+
+    pub fn timeout_secs(&self) -> u64 {
+        self.timeout.map_or(0, |timeout| timeout.as_secs())
+    }
+
+    pub fn memory_mib(&self) -> u64 {
+        self.memory.map_or(0, |bytes| bytes / (1024 * 1024))
+    }
+
+    pub fn is_set(&self) -> bool {
+        self.timeout.is_some() || self.memory.is_some()
+    }
+
+    /// Applies the memory limit to a command. The timeout is enforced while waiting for the command.
+    pub fn apply(&self, cmd: &mut Command) {
+        if let Some(bytes) = self.memory {
+            limit_memory(cmd, bytes);
+        }
+    }
+
+    /// Whether a child that ran with these limits exceeded the memory limit. Allocations beyond the limit fail, which
+    /// makes Rust programs abort.
+    pub fn is_out_of_memory(&self, status: ExitStatus) -> bool {
+        self.memory.is_some() && is_abort(status)
+    }
+
+    /// Describes why a day was stopped, e.g. `timed out after 10s`.
+    pub fn describe(&self, exceeded: LimitExceeded) -> String {
+        match (exceeded, self.timeout, self.memory) {
+            (LimitExceeded::Timeout, Some(timeout), _) => format!("timed out after {timeout:?}"),
+            (LimitExceeded::Memory, _, Some(bytes)) => {
+                format!("exceeded the memory limit of {}", format_bytes(bytes))
+            }
+            (exceeded, _, _) => exceeded.to_string(),
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn limit_memory(cmd: &mut Command, bytes: u64) {
+    use std::{io, os::unix::process::CommandExt};
+
+    #[allow(clippy::useless_conversion)]
+    let bytes = libc::rlim_t::try_from(bytes).unwrap_or(libc::RLIM_INFINITY);
+    let limit = libc::rlimit {
+        rlim_cur: bytes,
+        rlim_max: bytes,
+    };
+
+    // SAFETY: the closure runs between `fork` and `exec` and only calls `setrlimit`, which is async-signal-safe.
+    unsafe {
+        cmd.pre_exec(move || {
+            if libc::setrlimit(libc::RLIMIT_AS, &limit) == 0 {
+                Ok(())
+            } else {
+                Err(io::Error::last_os_error())
+            }
+        });
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn limit_memory(_cmd: &mut Command, _bytes: u64) {}
+
+#[cfg(target_os = "linux")]
+fn is_abort(status: ExitStatus) -> bool {
+    use std::os::unix::process::ExitStatusExt;
+
+    status.signal() == Some(libc::SIGABRT)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn is_abort(_status: ExitStatus) -> bool {
+    false
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl Display for LimitExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LimitExceeded::Timeout => write!(f, "timed out"),
+            LimitExceeded::Memory => write!(f, "out of memory"),
+        }
+    }
+}
+
+impl LimitExceeded {
+    /// Name of the limit in the timings file.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LimitExceeded::Timeout => "timeout",
+            LimitExceeded::Memory => "memory",
+        }
+    }
+}
+
+impl FromStr for LimitExceeded {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "timeout" => Ok(LimitExceeded::Timeout),
+            "memory" => Ok(LimitExceeded::Memory),
+            _ => Err(format!("unknown limit `{s}`.")),
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::time::Duration;
+
+    use super::{LimitExceeded, Limits};
+
+    #[test]
+    fn converts_units() {
+        let limits = Limits::new(10, 512);
+        assert_eq!(limits.timeout, Some(Duration::from_secs(10)));
+        assert_eq!(limits.memory, Some(512 * 1024 * 1024));
+        assert_eq!((limits.timeout_secs(), limits.memory_mib()), (10, 512));
+
+        assert_eq!(Limits::new(0, 0), Limits::default());
+        assert!(!Limits::default().is_set());
+    }
+
+    #[test]
+    fn describes_exceeded_limits() {
+        let limits = Limits::new(10, 512);
+        assert_eq!(
+            limits.describe(LimitExceeded::Timeout),
+            "timed out after 10s"
+        );
+        assert_eq!(
+            limits.describe(LimitExceeded::Memory),
+            "exceeded the memory limit of 512.0 MiB"
+        );
+        assert_eq!(
+            Limits::default().describe(LimitExceeded::Memory),
+            "out of memory"
+        );
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn stops_children() {
+        use std::process::Command;
+
+        use crate::template::run_multi::child_commands::run_command;
+
+        let limits = Limits::new(1, 0);
+        let mut cmd = Command::new("sleep");
+        cmd.arg("30");
+
+        let run = run_command(&mut cmd, true, &limits).unwrap();
+        assert_eq!(run.exceeded, Some(LimitExceeded::Timeout));
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn detects_aborts_at_the_memory_limit() {
+        use std::process::Command;
+
+        use crate::template::run_multi::child_commands::run_command;
+
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "kill -ABRT $$"]);
+
+        let run = run_command(&mut cmd, true, &Limits::new(0, 512)).unwrap();
+        assert_eq!(run.exceeded, Some(LimitExceeded::Memory));
+
+        let run = run_command(&mut cmd, true, &Limits::default()).unwrap();
+        assert_eq!(run.exceeded, None);
+    }
+}
@@ -1,68 +1,166 @@
 use std::{env, fs};
 
-pub mod aoc_cli;
+pub mod alloc_stats;
+pub mod answers;
+pub mod aoc_client;
+pub mod bench;
 pub mod commands;
+pub mod config;
+pub mod expect;
+pub mod limits;
+pub mod paths;
+pub mod protocol;
+pub mod registry;
 pub mod runner;
+pub mod submissions;
+pub mod test_report;
 
 pub use day::*;
+pub use puzzle::*;
+pub use year::*;
 
+mod benchmark_chart;
 mod day;
+mod dhat_profile;
+mod extract;
+mod html;
+mod puzzle;
 mod readme_benchmarks;
 mod run_multi;
 mod timings;
+mod timings_history;
+mod year;
 
 pub const ANSI_ITALIC: &str = "\x1b[3m";
 pub const ANSI_BOLD: &str = "\x1b[1m";
 pub const ANSI_RESET: &str = "\x1b[0m";
 
+// NOTE: the allocator is declared in the library so it applies to every binary, including the
+// `solutions` binary that contains all solutions.
+#[cfg(feature = "dhat-heap")]
+#[global_allocator]
+static ALLOC: dhat::Alloc = dhat::Alloc;
+
+#[cfg(all(feature = "alloc-stats", not(feature = "dhat-heap")))]
+#[global_allocator]
+static ALLOC: alloc_stats::CountingAlloc = alloc_stats::CountingAlloc;
+
 /// Helper function that reads a text file to a string.
 #[must_use]
-pub fn read_file(folder: &str, day: Day) -> String {
+pub fn read_file(folder: &str, puzzle: PuzzleId) -> String {
     let cwd = env::current_dir().unwrap();
-    let filepath = cwd.join("data").join(folder).join(format!("{day}.txt"));
+    let filepath = cwd.join(paths::data_file(folder, puzzle));
     let f = fs::read_to_string(filepath);
     f.expect("could not open input file")
 }
 
 /// Helper function that reads a text file to string, appending a part suffix. E.g. like `01-2.txt`.
 #[must_use]
-pub fn read_file_part(folder: &str, day: Day, part: u8) -> String {
+pub fn read_file_part(folder: &str, puzzle: PuzzleId, part: u8) -> String {
     let cwd = env::current_dir().unwrap();
-    let filepath = cwd
-        .join("data")
-        .join(folder)
-        .join(format!("{day}-{part}.txt"));
+    let filepath = cwd.join(paths::data_file_part(folder, puzzle, part));
     let f = fs::read_to_string(filepath);
     f.expect("could not open input file")
 }
 
-/// Creates the constant `DAY` and sets up the input and runner for each part.
+/// Helper function that reads the input of a solution binary.
+/// Reads the file passed with `--input <path>` if present, and the puzzle input otherwise.
+#[must_use]
+pub fn read_input(puzzle: PuzzleId) -> String {
+    match custom_input() {
+        Some(path) => fs::read_to_string(path).expect("could not open input file"),
+        None => read_file("inputs", puzzle),
+    }
+}
+
+/// Returns the path passed with `--input <path>`, if the solution binary runs against a custom input.
+#[must_use]
+pub fn custom_input() -> Option<String> {
+    let args: Vec<String> = env::args().collect();
+    let index = args.iter().position(|x| x == "--input")?;
+    args.get(index + 1).cloned()
+}
+
+/// Creates the constants `YEAR`, `DAY` and `PUZZLE` and sets up the input and runner for each part.
+/// Also creates the static `SOLUTION` which registers the solution with the `solutions` binary.
+///
+/// The optional, third parameter (1 or 2) allows you to only run a single part of the solution.
 ///
-/// The optional, second parameter (1 or 2) allows you to only run a single part of the solution.
+/// The optional `parse = <fn>` parameter adds a parse step. Its output is passed by reference to both parts,
+/// and its duration is timed separately from the parts.
 #[macro_export]
 macro_rules! solution {
-    ($day:expr) => {
-        $crate::solution!(@impl $day, [part_one, 1] [part_two, 2]);
+    ($year:expr, $day:expr) => {
+        $crate::solution!(@impl $year, $day, [part_one, 1] [part_two, 2]);
+    };
+    ($year:expr, $day:expr, 1) => {
+        $crate::solution!(@impl $year, $day, [part_one, 1]);
+    };
+    ($year:expr, $day:expr, 2) => {
+        $crate::solution!(@impl $year, $day, [part_two, 2]);
+    };
+    ($year:expr, $day:expr, parse = $parse:expr) => {
+        $crate::solution!(@impl_parsed $year, $day, $parse, [part_one, 1] [part_two, 2]);
     };
-    ($day:expr, 1) => {
-        $crate::solution!(@impl $day, [part_one, 1]);
+    ($year:expr, $day:expr, 1, parse = $parse:expr) => {
+        $crate::solution!(@impl_parsed $year, $day, $parse, [part_one, 1]);
     };
-    ($day:expr, 2) => {
-        $crate::solution!(@impl $day, [part_two, 2]);
+    ($year:expr, $day:expr, 2, parse = $parse:expr) => {
+        $crate::solution!(@impl_parsed $year, $day, $parse, [part_two, 2]);
     };
 
-    (@impl $day:expr, $( [$func:expr, $part:expr] )*) => {
-        /// The current day.
-        const DAY: $crate::template::Day = $crate::day!($day);
+    (@impl $year:expr, $day:expr, $( [$func:expr, $part:expr] )*) => {
+        $crate::solution!(@consts $year, $day);
 
-        #[cfg(feature = "dhat-heap")]
-        #[global_allocator]
-        static ALLOC: dhat::Alloc = dhat::Alloc;
+        /// Registers this solution with the `solutions` binary.
+        #[allow(dead_code)]
+        pub static SOLUTION: $crate::template::registry::Solution =
+            $crate::template::registry::Solution {
+                puzzle: PUZZLE,
+                run: |input, is_timed| {
+                    use $crate::template::runner::*;
+                    vec![$( run_part_with($func, input, PUZZLE, $part, is_timed), )*]
+                },
+            };
 
         fn main() {
             use $crate::template::runner::*;
-            let input = $crate::template::read_file("inputs", DAY);
-            $( run_part($func, &input, DAY, $part); )*
+            let input = $crate::template::read_input(PUZZLE);
+            $( run_part($func, &input, PUZZLE, $part); )*
         }
     };
+
+    (@impl_parsed $year:expr, $day:expr, $parse:expr, $( [$func:expr, $part:expr] )*) => {
+        $crate::solution!(@consts $year, $day);
+
+        /// Registers this solution with the `solutions` binary.
+        #[allow(dead_code)]
+        pub static SOLUTION: $crate::template::registry::Solution =
+            $crate::template::registry::Solution {
+                puzzle: PUZZLE,
+                run: |input, is_timed| {
+                    use $crate::template::runner::*;
+                    let (parsed, parse_result) = run_parse_with($parse, input, is_timed);
+                    vec![parse_result, $( run_part_with($func, &parsed, PUZZLE, $part, is_timed), )*]
+                },
+            };
+
+        fn main() {
+            use $crate::template::runner::*;
+            let input = $crate::template::read_input(PUZZLE);
+            let parsed = run_parse($parse, &input);
+            $( run_part($func, &parsed, PUZZLE, $part); )*
+        }
+    };
+
+    (@consts $year:expr, $day:expr) => {
+        /// The event year of this puzzle.
+        #[allow(dead_code)]
+        const YEAR: $crate::template::Year = $crate::year!($year);
+        /// The day of this puzzle.
+        #[allow(dead_code)]
+        const DAY: $crate::template::Day = $crate::day!($day);
+        /// The current puzzle.
+        const PUZZLE: $crate::template::PuzzleId = $crate::template::PuzzleId::new(YEAR, DAY);
+    };
 }
@@ -0,0 +1,96 @@
+/// Resolves the locations of the files that belong to a puzzle.
+///
+/// Data files are namespaced by event year, e.g. `data/2024/inputs/01.txt`.
+/// Solution binaries are named after the [`PuzzleId`], e.g. `src/bin/2024-01.rs`.
+use crate::template::{config::config, PuzzleId, Year};
+
+/// Returns the data directory of `folder` for a given year, e.g. `data/2024/inputs`.
+#[must_use]
+pub fn data_dir(year: Year, folder: &str) -> String {
+    format!("{}/{year}/{folder}", config().paths.data)
+}
+
+/// Returns the path of a text file in a data directory, e.g. `data/2024/examples/01.txt`.
+#[must_use]
+pub fn data_file(folder: &str, puzzle: PuzzleId) -> String {
+    format!("{}/{}.txt", data_dir(puzzle.year, folder), puzzle.day)
+}
+
+/// Returns the path of a text file with a part suffix in a data directory, e.g. `data/2024/examples/01-2.txt`.
+#[must_use]
+pub fn data_file_part(folder: &str, puzzle: PuzzleId, part: u8) -> String {
+    format!(
+        "{}/{}-{part}.txt",
+        data_dir(puzzle.year, folder),
+        puzzle.day
+    )
+}
+
+#[must_use]
+pub fn input(puzzle: PuzzleId) -> String {
+    data_file("inputs", puzzle)
+}
+
+#[must_use]
+pub fn example(puzzle: PuzzleId) -> String {
+    data_file("examples", puzzle)
+}
+
+/// Returns the path of an additional example written by `cargo examples`, e.g. `data/2024/examples/01-example-2.txt`.
+/// These are named apart from part files like `01-2.txt`, which hold the example of a part.
+#[must_use]
+pub fn extra_example(puzzle: PuzzleId, index: usize) -> String {
+    format!(
+        "{}/{}-example-{index}.txt",
+        data_dir(puzzle.year, "examples"),
+        puzzle.day
+    )
+}
+
+/// Returns the path of the expected answers for the examples of a puzzle, e.g. `data/2024/examples/01.expect.toml`.
+#[must_use]
+pub fn example_expectations(puzzle: PuzzleId) -> String {
+    format!(
+        "{}/{}.expect.toml",
+        data_dir(puzzle.year, "examples"),
+        puzzle.day
+    )
+}
+
+#[must_use]
+pub fn puzzle_description(puzzle: PuzzleId) -> String {
+    format!("{}/{}.md", data_dir(puzzle.year, "puzzles"), puzzle.day)
+}
+
+#[must_use]
+pub fn bin(puzzle: PuzzleId) -> String {
+    format!("./src/bin/{puzzle}.rs")
+}
+
+/// Returns the path of the benchmark chart of a year, e.g. `.assets/benchmarks-2024.svg`.
+#[must_use]
+pub fn benchmark_chart(year: Year) -> String {
+    format!("{}/benchmarks-{year}.svg", config().paths.assets)
+}
+
+#[must_use]
+pub fn templates_dir() -> String {
+    config().paths.templates.clone()
+}
+
+/// Returns the path of a scaffold template, e.g. `templates/grid.txt`.
+#[must_use]
+pub fn template(name: &str) -> String {
+    format!("{}/{name}.txt", templates_dir())
+}
+
+/// Returns the path of a file in the data directory that is shared by all years, e.g. `data/timings.json`.
+#[must_use]
+pub fn shared_data_file(name: &str) -> String {
+    format!("{}/{name}", config().paths.data)
+}
+
+#[must_use]
+pub fn readme() -> String {
+    config().paths.readme.clone()
+}
@@ -0,0 +1,320 @@
+/// Machine-readable protocol between the runner and solution binaries.
+///
+/// When a solution binary is invoked with [`REPORT_FLAG`], it emits one JSON record per part on stdout,
+/// prefixed with [`REPORT_PREFIX`]. The runner picks up these records instead of parsing the human-readable output.
+use std::{collections::HashMap, fmt::Display, str::FromStr, time::Duration};
+use tinyjson::JsonValue;
+
+use crate::template::{
+    alloc_stats::AllocStats,
+    bench::BenchStats,
+    runner::{PartResult, PARSE_STEP},
+};
+
+/// Passed to a solution binary to enable reports.
+pub const REPORT_FLAG: &str = "--aoc-report";
+
+/// Marks a line of output as a report record.
+pub const REPORT_PREFIX: &str = "@aoc-report ";
+
+/// Status of a solution part.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PartStatus {
+    /// The part returned a result that matches the known answer.
+    Correct,
+    /// The part returned a result that does not match the known answer.
+    Wrong,
+    /// The part returned a result, but no answer is known. Also used for the parse step.
+    Unchecked,
+    /// The part did not return a result.
+    Unsolved,
+    /// The part returned an error or panicked.
+    Failed,
+}
+
+impl PartStatus {
+    pub fn of(result: &PartResult) -> Self {
+        if result.part == PARSE_STEP {
+            return PartStatus::Unchecked;
+        }
+
+        if result.error.is_some() {
+            return PartStatus::Failed;
+        }
+
+        match (&result.answer, result.check) {
+            (None, _) => PartStatus::Unsolved,
+            (Some(_), Some(true)) => PartStatus::Correct,
+            (Some(_), Some(false)) => PartStatus::Wrong,
+            (Some(_), None) => PartStatus::Unchecked,
+        }
+    }
+
+    fn check(self) -> Option<bool> {
+        match self {
+            PartStatus::Correct => Some(true),
+            PartStatus::Wrong => Some(false),
+            PartStatus::Unchecked | PartStatus::Unsolved | PartStatus::Failed => None,
+        }
+    }
+}
+
+impl Display for PartStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            PartStatus::Correct => "correct",
+            PartStatus::Wrong => "wrong",
+            PartStatus::Unchecked => "unchecked",
+            PartStatus::Unsolved => "unsolved",
+            PartStatus::Failed => "failed",
+        };
+        write!(f, "{s}")
+    }
+}
+
+impl FromStr for PartStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "correct" => Ok(PartStatus::Correct),
+            "wrong" => Ok(PartStatus::Wrong),
+            "unchecked" => Ok(PartStatus::Unchecked),
+            "unsolved" => Ok(PartStatus::Unsolved),
+            "failed" => Ok(PartStatus::Failed),
+            _ => Err(format!("unknown part status `{s}`.")),
+        }
+    }
+}
+
+/// Formats a part result as a report line.
+pub fn format_report(result: &PartResult) -> String {
+    let json = JsonValue::from(result);
+    format!("{REPORT_PREFIX}{}", json.stringify().unwrap())
+}
+
+/// Parses a line of solution output.
+/// Returns `None` if the line is not a report record.
+pub fn parse_report(line: &str) -> Option<Result<PartResult, String>> {
+    let record = line.strip_prefix(REPORT_PREFIX)?;
+    let json = match JsonValue::from_str(record) {
+        Ok(json) => json,
+        Err(_) => return Some(Err("report is not valid JSON.".into())),
+    };
+    Some(PartResult::try_from(&json))
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<&PartResult> for JsonValue {
+    fn from(value: &PartResult) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert("part".into(), JsonValue::Number(f64::from(value.part)));
+
+        map.insert(
+            "answer".into(),
+            value
+                .answer
+                .clone()
+                .map_or(JsonValue::Null, JsonValue::String),
+        );
+
+        map.insert(
+            "error".into(),
+            value
+                .error
+                .clone()
+                .map_or(JsonValue::Null, JsonValue::String),
+        );
+
+        #[allow(clippy::cast_precision_loss)]
+        map.insert(
+            "duration_nanos".into(),
+            JsonValue::Number(value.duration.as_nanos() as f64),
+        );
+
+        #[allow(clippy::cast_precision_loss)]
+        map.insert("samples".into(), JsonValue::Number(value.samples as f64));
+
+        map.insert(
+            "stats".into(),
+            value
+                .stats
+                .as_ref()
+                .map_or(JsonValue::Null, JsonValue::from),
+        );
+
+        map.insert(
+            "alloc".into(),
+            value
+                .alloc
+                .as_ref()
+                .map_or(JsonValue::Null, JsonValue::from),
+        );
+
+        map.insert(
+            "status".into(),
+            JsonValue::String(PartStatus::of(value).to_string()),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for PartResult {
+    type Error = String;
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected report to be a JSON object.")?;
+
+        let part = json
+            .get("part")
+            .and_then(|v| v.get::<f64>())
+            .ok_or("Expected report.part to be a number.")?;
+
+        let answer = json
+            .get("answer")
+            .map(|v| if v.is_null() { None } else { v.get::<String>() })
+            .ok_or("Expected report.answer to be null or string.")?;
+
+        let error = match json.get("error") {
+            None | Some(JsonValue::Null) => None,
+            Some(error) => Some(
+                error
+                    .get::<String>()
+                    .ok_or("Expected report.error to be null or string.")?
+                    .clone(),
+            ),
+        };
+
+        let duration_nanos = json
+            .get("duration_nanos")
+            .and_then(|v| v.get::<f64>())
+            .ok_or("Expected report.duration_nanos to be a number.")?;
+
+        let samples = json
+            .get("samples")
+            .and_then(|v| v.get::<f64>())
+            .ok_or("Expected report.samples to be a number.")?;
+
+        // stats are only reported for benched parts.
+        let stats = match json.get("stats") {
+            None | Some(JsonValue::Null) => None,
+            Some(stats) => Some(BenchStats::try_from(stats)?),
+        };
+
+        // allocations are only reported if the `alloc-stats` feature is enabled.
+        let alloc = match json.get("alloc") {
+            None | Some(JsonValue::Null) => None,
+            Some(alloc) => Some(AllocStats::try_from(alloc)?),
+        };
+
+        let status = json
+            .get("status")
+            .and_then(|v| v.get::<String>())
+            .ok_or("Expected report.status to be a string.")?
+            .parse::<PartStatus>()?;
+
+        Ok(PartResult {
+            part: *part as u8,
+            answer: answer.cloned(),
+            error,
+            duration: Duration::from_nanos(*duration_nanos as u64),
+            samples: *samples as u128,
+            stats,
+            alloc,
+            check: status.check(),
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::time::Duration;
+
+    use super::{format_report, parse_report, PartStatus};
+    use crate::template::{alloc_stats::AllocStats, bench::BenchStats, runner::PartResult};
+
+    fn get_mock_result() -> PartResult {
+        PartResult {
+            part: 2,
+            answer: Some("(1, 2) @ 3ms samples)".into()),
+            error: None,
+            duration: Duration::from_nanos(74_130),
+            samples: 100_000,
+            stats: Some(BenchStats {
+                samples: 100_000,
+                mean: Duration::from_nanos(74_130),
+                median: Duration::from_nanos(74_000),
+                min: Duration::from_nanos(70_000),
+                max: Duration::from_nanos(90_000),
+                std_dev: Duration::from_nanos(1_000),
+                outliers: 12,
+            }),
+            alloc: Some(AllocStats {
+                peak_bytes: 2048,
+                allocations: 3,
+                allocated_bytes: 4096,
+            }),
+            check: Some(false),
+        }
+    }
+
+    #[test]
+    fn roundtrips_reports() {
+        let line = format_report(&get_mock_result());
+        let result = parse_report(&line).unwrap().unwrap();
+
+        assert_eq!(result.part, 2);
+        assert_eq!(result.answer.as_deref(), Some("(1, 2) @ 3ms samples)"));
+        assert_eq!(result.duration, Duration::from_nanos(74_130));
+        assert_eq!(result.samples, 100_000);
+        assert_eq!(result.stats, get_mock_result().stats);
+        assert_eq!(result.alloc, get_mock_result().alloc);
+        assert_eq!(result.check, Some(false));
+        assert_eq!(PartStatus::of(&result), PartStatus::Wrong);
+    }
+
+    #[test]
+    fn ignores_regular_output() {
+        assert!(parse_report("Part 1: 0 (74.13ns @ 100000 samples)").is_none());
+        assert!(parse_report("").is_none());
+    }
+
+    #[test]
+    fn rejects_malformed_reports() {
+        assert!(parse_report("@aoc-report {").unwrap().is_err());
+        assert!(parse_report(r#"@aoc-report { "part": 1 }"#)
+            .unwrap()
+            .is_err());
+    }
+
+    #[test]
+    fn handles_unsolved_parts() {
+        let line = r#"@aoc-report { "part": 1, "answer": null, "duration_nanos": 10, "samples": 1, "status": "unsolved" }"#;
+        let result = parse_report(line).unwrap().unwrap();
+        assert!(result.answer.is_none());
+        assert_eq!(PartStatus::of(&result), PartStatus::Unsolved);
+    }
+
+    #[test]
+    fn handles_failed_parts() {
+        let mut result = get_mock_result();
+        result.answer = None;
+        result.error = Some("panicked: index out of bounds".into());
+
+        let result = parse_report(&format_report(&result)).unwrap().unwrap();
+        assert_eq!(
+            result.error.as_deref(),
+            Some("panicked: index out of bounds")
+        );
+        assert_eq!(result.check, None);
+        assert_eq!(PartStatus::of(&result), PartStatus::Failed);
+    }
+}
@@ -0,0 +1,110 @@
+use std::error::Error;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::template::{all_days, Day, Year};
+
+/// Identifies a single puzzle of advent of code by its event year and day.
+///
+/// # Display
+/// This value displays as `<year>-<day>`. This is also the name of the solution binary.
+///
+/// ```
+/// # use advent_of_code::{Day, PuzzleId, Year};
+/// let puzzle = PuzzleId::new(Year::new(2024).unwrap(), Day::new(8).unwrap());
+/// assert_eq!(puzzle.to_string(), "2024-08")
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PuzzleId {
+    pub year: Year,
+    pub day: Day,
+}
+
+impl PuzzleId {
+    /// Creates a [`PuzzleId`] from a year and a day.
+    pub const fn new(year: Year, day: Day) -> Self {
+        Self { year, day }
+    }
+}
+
+#[cfg(feature = "today")]
+impl PuzzleId {
+    /// Returns the current puzzle if it's between the 1st and the 25th of december, `None` otherwise.
+    pub fn today() -> Option<Self> {
+        Some(Self::new(Year::today()?, Day::today()?))
+    }
+}
+
+impl Display for PuzzleId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.year, self.day)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl FromStr for PuzzleId {
+    type Err = PuzzleIdFromStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (year, day) = s.split_once('-').ok_or(PuzzleIdFromStrError)?;
+        Ok(Self::new(
+            year.parse().map_err(|_| PuzzleIdFromStrError)?,
+            day.parse().map_err(|_| PuzzleIdFromStrError)?,
+        ))
+    }
+}
+
+/// An error which can be returned when parsing a [`PuzzleId`].
+#[derive(Debug)]
+pub struct PuzzleIdFromStrError;
+
+impl Error for PuzzleIdFromStrError {}
+
+impl Display for PuzzleIdFromStrError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("expecting a puzzle in the format `<year>-<day>`, e.g. `2024-01`")
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// An iterator that yields every puzzle of an event year from the 1st to the 25th.
+pub fn all_puzzles(year: Year) -> impl Iterator<Item = PuzzleId> {
+    all_days().map(move |day| PuzzleId::new(year, day))
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Creates a [`PuzzleId`] value in a const context.
+#[macro_export]
+macro_rules! puzzle {
+    ($year:expr, $day:expr) => {
+        $crate::template::PuzzleId::new($crate::year!($year), $crate::day!($day))
+    };
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::PuzzleId;
+
+    #[test]
+    fn displays_puzzle_ids() {
+        assert_eq!(puzzle!(2023, 4).to_string(), "2023-04");
+    }
+
+    #[test]
+    fn parses_puzzle_ids() {
+        assert_eq!("2023-04".parse::<PuzzleId>().unwrap(), puzzle!(2023, 4));
+        assert_eq!("2023-4".parse::<PuzzleId>().unwrap(), puzzle!(2023, 4));
+    }
+
+    #[test]
+    fn rejects_invalid_puzzle_ids() {
+        assert!("2023".parse::<PuzzleId>().is_err());
+        assert!("2023-26".parse::<PuzzleId>().is_err());
+        assert!("04-2023".parse::<PuzzleId>().is_err());
+    }
+}
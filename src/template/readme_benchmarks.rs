@@ -1,9 +1,12 @@
 /// Module that updates the readme me with timing information.
 /// The approach taken is similar to how `aoc-readme-stars` handles this.
-use std::{fs, io};
+use std::{cmp::Reverse, fs, io, path::Path};
 
-use crate::template::timings::Timings;
-use crate::template::Day;
+use crate::template::alloc_stats::format_bytes;
+use crate::template::config::{config, BenchmarkColumn, BenchmarkOrder, ReadmeConfig};
+use crate::template::limits::LimitExceeded;
+use crate::template::timings::{PartTiming, Timing, Timings};
+use crate::template::{aoc_client, benchmark_chart, extract, paths, PuzzleId, Year};
 
 static MARKER: &str = "<!--- benchmarking table --->";
 
@@ -25,11 +28,6 @@ pub struct TablePosition {
     pos_end: usize,
 }
 
-#[must_use]
-pub fn get_path_for_bin(day: Day) -> String {
-    format!("./src/bin/{day}.rs")
-}
-
 fn locate_table(readme: &str) -> Result<TablePosition, Error> {
     let matches: Vec<_> = readme.match_indices(MARKER).collect();
 
@@ -52,76 +50,250 @@ fn locate_table(readme: &str) -> Result<TablePosition, Error> {
     Ok(TablePosition { pos_start, pos_end })
 }
 
-fn construct_table(prefix: &str, timings: Timings, total_millis: f64) -> String {
-    let header = format!("{prefix} Benchmarks");
+/// Returns the title of a puzzle if its description was downloaded.
+fn puzzle_title(puzzle: PuzzleId) -> Option<String> {
+    fs::read_to_string(paths::puzzle_description(puzzle))
+        .ok()
+        .and_then(|description| extract::title(&description))
+}
 
-    let mut lines: Vec<String> = vec![
-        MARKER.into(),
-        header,
-        String::new(),
-        "| Day | Part 1 | Part 2 |".into(),
-        "| :---: | :---: | :---:  |".into(),
+fn construct_year_table(
+    prefix: &str,
+    year: Year,
+    mut timings: Timings,
+    config: &ReadmeConfig,
+) -> Vec<String> {
+    let total_millis = timings.total_millis();
+    let has_column = |column| config.columns.contains(&column);
+
+    // only show the parse column if at least one solution has a parse step.
+    let has_parse =
+        has_column(BenchmarkColumn::Parse) && timings.data.iter().any(|t| t.parse.is_some());
+
+    let mut by_runtime: Vec<&Timing> = timings.data.iter().collect();
+    by_runtime.sort_by_key(|t| (Reverse(t.total()), t.puzzle));
+    let slowest: Vec<PuzzleId> = by_runtime
+        .iter()
+        .take(config.highlight_slowest)
+        .map(|t| t.puzzle)
+        .collect();
+
+    if config.order == BenchmarkOrder::Slowest {
+        timings.data.sort_by_key(|t| (Reverse(t.total()), t.puzzle));
+    }
+
+    let columns = [
+        (true, "Day"),
+        (has_column(BenchmarkColumn::Puzzle), "Puzzle"),
+        (has_parse, "Parse"),
+        (true, "Part 1"),
+        (true, "Part 2"),
+        (has_column(BenchmarkColumn::Total), "Total"),
+        (has_column(BenchmarkColumn::Share), "Share"),
+        (has_column(BenchmarkColumn::Samples), "Samples"),
+        (has_column(BenchmarkColumn::Memory), "Peak memory"),
     ];
+    let headers: Vec<&str> = columns
+        .iter()
+        .filter(|(shown, _)| *shown)
+        .map(|(_, header)| *header)
+        .collect();
 
-    for timing in timings.data {
-        let path = get_path_for_bin(timing.day);
+    let mut lines: Vec<String> = vec![format!("{prefix} {year}"), String::new()];
+
+    if config.chart && !timings.data.is_empty() {
         lines.push(format!(
-            "| [Day {}]({}) | `{}` | `{}` |",
-            timing.day.into_inner(),
-            path,
-            timing.part_1.unwrap_or_else(|| "-".into()),
-            timing.part_2.unwrap_or_else(|| "-".into())
+            "![{year} benchmarks](./{})",
+            paths::benchmark_chart(year)
         ));
+        lines.push(String::new());
+    }
+    lines.push(format!("| {} |", headers.join(" | ")));
+    lines.push(format!(
+        "|{}  |",
+        vec![" :---: "; headers.len()].join("|").trim_end()
+    ));
+
+    // parts that did not finish because the day exceeded a limit show the limit instead.
+    let format = |part: Option<PartTiming>, limit: Option<LimitExceeded>| match (part, limit) {
+        (Some(part), _) => part.to_string(),
+        (None, Some(limit)) => limit.to_string(),
+        (None, None) => "-".into(),
+    };
+    let samples =
+        |part: Option<PartTiming>| part.map_or_else(|| "-".into(), |t| t.samples.to_string());
+
+    let memory = |part: Option<PartTiming>| {
+        part.and_then(|t| t.alloc)
+            .map_or_else(|| "-".into(), |alloc| format_bytes(alloc.peak_bytes))
+    };
+
+    for timing in timings.data {
+        let day = format!(
+            "[Day {}]({})",
+            timing.puzzle.day.into_inner(),
+            paths::bin(timing.puzzle)
+        );
+
+        let mut cells = vec![if slowest.contains(&timing.puzzle) {
+            format!("**{day}**")
+        } else {
+            day
+        }];
+
+        if has_column(BenchmarkColumn::Puzzle) {
+            cells.push(format!(
+                "[{}]({})",
+                puzzle_title(timing.puzzle).unwrap_or_else(|| "Puzzle".into()),
+                aoc_client::description_url(timing.puzzle)
+            ));
+        }
+        if has_parse {
+            cells.push(format!("`{}`", format(timing.parse, timing.limit)));
+        }
+        cells.push(format!("`{}`", format(timing.part_1, timing.limit)));
+        cells.push(format!("`{}`", format(timing.part_2, timing.limit)));
+        if has_column(BenchmarkColumn::Total) {
+            cells.push(format!("`{:.1?}`", timing.total()));
+        }
+        if has_column(BenchmarkColumn::Share) {
+            let share = if total_millis > 0.0 {
+                timing.total().as_secs_f64() * 1000.0 / total_millis * 100.0
+            } else {
+                0.0
+            };
+            cells.push(format!("{share:.1}%"));
+        }
+        if has_column(BenchmarkColumn::Samples) {
+            cells.push(format!(
+                "{} / {}",
+                samples(timing.part_1),
+                samples(timing.part_2)
+            ));
+        }
+
+        if has_column(BenchmarkColumn::Memory) {
+            cells.push(format!(
+                "{} / {}",
+                memory(timing.part_1),
+                memory(timing.part_2)
+            ));
+        }
+
+        lines.push(format!("| {} |", cells.join(" | ")));
     }
 
     lines.push(String::new());
     lines.push(format!("**Total: {total_millis:.2}ms**"));
+
+    lines
+}
+
+fn construct_table(prefix: &str, timings: &Timings, config: &ReadmeConfig) -> String {
+    let header = format!("{prefix} Benchmarks");
+
+    let mut lines: Vec<String> = vec![MARKER.into(), header];
+
+    let mut years: Vec<Year> = timings.data.iter().map(|t| t.puzzle.year).collect();
+    years.dedup();
+
+    for year in years {
+        lines.push(String::new());
+        lines.append(&mut construct_year_table(
+            &format!("{prefix}#"),
+            year,
+            timings.for_year(year),
+            config,
+        ));
+    }
+
     lines.push(MARKER.into());
 
     lines.join("\n")
 }
 
-fn update_content(s: &mut String, timings: Timings, total_millis: f64) -> Result<(), Error> {
+fn update_content(s: &mut String, timings: &Timings, config: &ReadmeConfig) -> Result<(), Error> {
     let positions = locate_table(s)?;
-    let table = construct_table("##", timings, total_millis);
+    let table = construct_table("##", timings, config);
     s.replace_range(positions.pos_start..positions.pos_end, &table);
     Ok(())
 }
 
+/// Renders the benchmark chart of every year to the assets directory.
+fn write_charts(timings: &Timings, config: &ReadmeConfig) -> Result<(), Error> {
+    let mut years: Vec<Year> = timings.data.iter().map(|t| t.puzzle.year).collect();
+    years.dedup();
+
+    for year in years {
+        if let Some(svg) =
+            benchmark_chart::render(year, &timings.for_year(year), config.chart_scale)
+        {
+            let path = paths::benchmark_chart(year);
+            if let Some(dir) = Path::new(&path).parent() {
+                fs::create_dir_all(dir)?;
+            }
+            fs::write(&path, svg)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes the benchmark table to the readme, laid out as configured in the `[readme]` section of `aoc.toml`.
+/// Timings are expected to be sorted by puzzle.
 pub fn update(timings: Timings) -> Result<(), Error> {
-    let path = "README.md";
-    let mut readme = String::from_utf8_lossy(&fs::read(path)?).to_string();
-    let total_millis = timings.total_millis();
-    update_content(&mut readme, timings, total_millis)?;
-    fs::write(path, &readme)?;
+    let config = &config().readme;
+
+    if config.chart {
+        write_charts(&timings, config)?;
+    }
+
+    let path = paths::readme();
+    let mut readme = String::from_utf8_lossy(&fs::read(&path)?).to_string();
+    update_content(&mut readme, &timings, config)?;
+    fs::write(&path, &readme)?;
     Ok(())
 }
 
 #[cfg(feature = "test_lib")]
 mod tests {
     use super::{update_content, MARKER};
-    use crate::{day, template::timings::Timing, template::timings::Timings};
+    use std::time::Duration;
+
+    use crate::{
+        puzzle,
+        template::alloc_stats::AllocStats,
+        template::config::{BenchmarkColumn, BenchmarkOrder, ReadmeConfig},
+        template::timings::{PartTiming, Timing, Timings},
+    };
+
+    fn part(millis: u64) -> Option<PartTiming> {
+        Some(PartTiming::new(Duration::from_millis(millis), None))
+    }
 
     fn get_mock_timings() -> Timings {
         Timings {
             data: vec![
                 Timing {
-                    day: day!(1),
-                    part_1: Some("10ms".into()),
-                    part_2: Some("20ms".into()),
-                    total_nanos: 3e+10,
+                    puzzle: puzzle!(2024, 1),
+                    parse: None,
+                    part_1: part(10),
+                    part_2: part(20),
+                    limit: None,
                 },
                 Timing {
-                    day: day!(2),
-                    part_1: Some("30ms".into()),
-                    part_2: Some("40ms".into()),
-                    total_nanos: 7e+10,
+                    puzzle: puzzle!(2024, 2),
+                    parse: None,
+                    part_1: part(30),
+                    part_2: part(40),
+                    limit: None,
                 },
                 Timing {
-                    day: day!(4),
-                    part_1: Some("40ms".into()),
-                    part_2: Some("50ms".into()),
-                    total_nanos: 9e+10,
+                    puzzle: puzzle!(2024, 4),
+                    parse: None,
+                    part_1: part(40),
+                    part_2: part(50),
+                    limit: None,
                 },
             ],
         }
@@ -131,28 +303,28 @@ mod tests {
     #[should_panic]
     fn errors_if_marker_not_present() {
         let mut s = "# readme".to_string();
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(&mut s, &get_mock_timings(), &ReadmeConfig::default()).unwrap();
     }
 
     #[test]
     #[should_panic]
     fn errors_if_too_many_markers_present() {
         let mut s = format!("{} {} {}", MARKER, MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(&mut s, &get_mock_timings(), &ReadmeConfig::default()).unwrap();
     }
 
     #[test]
     fn updates_empty_benchmarks() {
         let mut s = format!("foo\nbar\n{}{}\nbaz", MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
-        assert_eq!(s.contains("## Benchmarks"), true);
+        update_content(&mut s, &get_mock_timings(), &ReadmeConfig::default()).unwrap();
+        assert!(s.contains("## Benchmarks"));
     }
 
     #[test]
     fn updates_existing_benchmarks() {
         let mut s = format!("foo\nbar\n{}{}\nbaz", MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(&mut s, &get_mock_timings(), &ReadmeConfig::default()).unwrap();
+        update_content(&mut s, &get_mock_timings(), &ReadmeConfig::default()).unwrap();
         assert_eq!(s.matches(MARKER).collect::<Vec<&str>>().len(), 2);
         assert_eq!(s.matches("## Benchmarks").collect::<Vec<&str>>().len(), 1);
     }
@@ -160,18 +332,22 @@ mod tests {
     #[test]
     fn format_benchmarks() {
         let mut s = format!("foo\nbar\n{}\n{}\nbaz", MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(&mut s, &get_mock_timings(), &ReadmeConfig::default()).unwrap();
         let expected = [
             "foo",
             "bar",
             "<!--- benchmarking table --->",
             "## Benchmarks",
             "",
+            "### 2024",
+            "",
+            "![2024 benchmarks](./.assets/benchmarks-2024.svg)",
+            "",
             "| Day | Part 1 | Part 2 |",
             "| :---: | :---: | :---:  |",
-            "| [Day 1](./src/bin/01.rs) | `10ms` | `20ms` |",
-            "| [Day 2](./src/bin/02.rs) | `30ms` | `40ms` |",
-            "| [Day 4](./src/bin/04.rs) | `40ms` | `50ms` |",
+            "| [Day 1](./src/bin/2024-01.rs) | `10.0ms` | `20.0ms` |",
+            "| [Day 2](./src/bin/2024-02.rs) | `30.0ms` | `40.0ms` |",
+            "| [Day 4](./src/bin/2024-04.rs) | `40.0ms` | `50.0ms` |",
             "",
             "**Total: 190.00ms**",
             "<!--- benchmarking table --->",
@@ -180,4 +356,60 @@ mod tests {
         .join("\n");
         assert_eq!(s, expected);
     }
+
+    #[test]
+    fn format_benchmarks_with_parse_step() {
+        let mut timings = get_mock_timings();
+        timings.data[1].parse = part(5);
+
+        let mut s = format!("{}\n{}", MARKER, MARKER);
+        update_content(&mut s, &timings, &ReadmeConfig::default()).unwrap();
+
+        assert!(s.contains("| Day | Parse | Part 1 | Part 2 |"));
+        assert!(s.contains("| [Day 1](./src/bin/2024-01.rs) | `-` | `10.0ms` | `20.0ms` |"));
+        assert!(s.contains("| [Day 2](./src/bin/2024-02.rs) | `5.0ms` | `30.0ms` | `40.0ms` |"));
+    }
+
+    #[test]
+    fn format_benchmarks_with_columns() {
+        let config = ReadmeConfig {
+            columns: vec![
+                BenchmarkColumn::Puzzle,
+                BenchmarkColumn::Total,
+                BenchmarkColumn::Share,
+                BenchmarkColumn::Samples,
+                BenchmarkColumn::Memory,
+            ],
+            order: BenchmarkOrder::Slowest,
+            highlight_slowest: 1,
+            chart: false,
+            ..ReadmeConfig::default()
+        };
+
+        let mut timings = get_mock_timings();
+        timings.data[2].part_1.as_mut().unwrap().alloc = Some(AllocStats {
+            peak_bytes: 2048,
+            ..AllocStats::default()
+        });
+
+        let mut s = format!("{}\n{}", MARKER, MARKER);
+        update_content(&mut s, &timings, &config).unwrap();
+
+        let table: Vec<&str> = s.lines().skip(5).take(5).collect();
+        assert_eq!(
+            table[0],
+            "| Day | Puzzle | Part 1 | Part 2 | Total | Share | Samples | Peak memory |"
+        );
+        assert!(table[2].starts_with("| **[Day 4](./src/bin/2024-04.rs)** | ["));
+        assert!(table[2].ends_with(
+            "](https://adventofcode.com/2024/day/4) | `40.0ms` | `50.0ms` | `90.0ms` | 47.4% | 1 / 1 | 2.0 KiB / - |"
+        ));
+        assert!(table[3].starts_with("| [Day 2](./src/bin/2024-02.rs) |"));
+        assert!(table[4].starts_with("| [Day 1](./src/bin/2024-01.rs) |"));
+
+        // the table is stable across updates.
+        let updated = s.clone();
+        update_content(&mut s, &timings, &config).unwrap();
+        assert_eq!(s, updated);
+    }
 }
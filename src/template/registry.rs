@@ -0,0 +1,89 @@
+/// Registry of solutions which allows running all of them in a single process.
+///
+/// Every solution registers itself via the static `SOLUTION` created by the [`solution!`](crate::solution) macro.
+/// The build script collects these statics into the `solutions` binary, which `cargo all` and `cargo time` invoke
+/// instead of running a separate binary per day.
+use std::{collections::HashSet, env, ffi::OsString, process};
+
+use crate::template::{
+    limits::Limits,
+    protocol,
+    run_multi::{report_in_process, run_multi_in_process},
+    runner::PartResult,
+    test_report::ReportTarget,
+    timings_history, PuzzleId,
+};
+
+/// A solution registered by the [`solution!`](crate::solution) macro.
+pub struct Solution {
+    pub puzzle: PuzzleId,
+    /// Runs all parts of the solution against an input. The second parameter enables benchmarking.
+    pub run: fn(&str, bool) -> Vec<PartResult>,
+}
+
+/// Entry point of the `solutions` binary.
+///
+/// Usage: `solutions [--time] [--store] [--compare <percent>] [--jobs <n>] [--report <format> <path>] <puzzle>...`,
+/// e.g. `solutions --time 2024-01 2024-02`.
+///
+/// With `--aoc-report`, the puzzles are run without a summary and report their results like a solution binary does.
+pub fn run(solutions: &[&Solution]) {
+    let mut raw_args: Vec<OsString> = env::args_os().skip(1).collect();
+    let report = match ReportTarget::take_from_args(&mut raw_args) {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            process::exit(1);
+        }
+    };
+
+    let mut args = pico_args::Arguments::from_vec(raw_args);
+
+    let is_timed = args.contains("--time");
+    let store = args.contains("--store");
+    let is_report = args.contains(protocol::REPORT_FLAG);
+
+    let (jobs, compare) = match (
+        args.opt_value_from_str("--jobs"),
+        args.opt_value_from_str("--compare"),
+    ) {
+        (Ok(jobs), Ok(compare)) => (jobs.unwrap_or(1), compare),
+        (Err(e), _) | (_, Err(e)) => {
+            eprintln!("Error: {e}");
+            process::exit(1);
+        }
+    };
+
+    let puzzles: HashSet<PuzzleId> = match args
+        .finish()
+        .iter()
+        .map(|arg| arg.to_string_lossy().parse())
+        .collect()
+    {
+        Ok(puzzles) => puzzles,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            process::exit(1);
+        }
+    };
+
+    if is_report {
+        report_in_process(solutions, &puzzles, is_timed);
+        return;
+    }
+
+    let run = run_multi_in_process(solutions, &puzzles, is_timed, jobs, &Limits::from_env());
+
+    let regressed = match &run.timings {
+        Some(timings) if store || compare.is_some() => {
+            timings_history::record(timings, store, compare)
+        }
+        _ => false,
+    };
+
+    let report_failed = report.is_some_and(|report| !report.write(&run.days));
+
+    if regressed || run.has_failures() || report_failed {
+        process::exit(1);
+    }
+}
@@ -1,48 +1,622 @@
-use std::{collections::HashSet, io};
-
-use crate::template::{Day, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+use std::{
+    collections::HashSet,
+    fmt::Display,
+    fs, io,
+    panic::{self, AssertUnwindSafe},
+    path::Path,
+    process::{Command, ExitStatus},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc,
+    },
+    thread,
+    time::Duration,
+};
 
-use super::{
-    all_days,
-    timings::{Timing, Timings},
+use crate::template::{
+    limits::{LimitExceeded, Limits},
+    paths,
+    registry::Solution,
+    runner::{panic_message, PartResult, PARSE_STEP},
+    PuzzleId, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET,
 };
 
-pub fn run_multi(days_to_run: &HashSet<Day>, is_release: bool, is_timed: bool) -> Option<Timings> {
-    let mut timings: Vec<Timing> = Vec::with_capacity(days_to_run.len());
+use self::child_commands::{ChildRun, Output};
+use super::timings::{PartTiming, Timing, Timings};
+
+/// The outcome of running a set of puzzles.
+pub struct MultiRun {
+    /// Benchmark times, only present for timed runs.
+    pub timings: Option<Timings>,
+    /// Outcome of every day, in day order.
+    pub days: Vec<DayResult>,
+}
+
+impl MultiRun {
+    /// Whether a day failed. Used to exit with an error, so CI can be gated on `cargo all`.
+    pub fn has_failures(&self) -> bool {
+        self.days.iter().any(|day| day.status == DayStatus::Failed)
+    }
+}
+
+/// The outcome of a single day.
+pub struct DayResult {
+    pub puzzle: PuzzleId,
+    pub status: DayStatus,
+    /// Results of the steps that finished.
+    pub parts: Vec<PartResult>,
+    /// Why the day was skipped or failed outside of its parts.
+    pub message: Option<String>,
+}
+
+/// Status of a day after running it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DayStatus {
+    /// Every part returned an answer.
+    Solved,
+    /// A part did not return an answer yet.
+    Unsolved,
+    /// A part failed or returned a wrong answer, or the day exceeded a limit or exited with an error.
+    Failed,
+    /// The day has no solution or no input yet.
+    Skipped,
+}
 
-    let mut need_space = false;
+impl Display for DayStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DayStatus::Solved => write!(f, "Solved"),
+            DayStatus::Unsolved => write!(f, "Unsolved"),
+            DayStatus::Failed => write!(f, "Failed"),
+            DayStatus::Skipped => write!(f, "Skipped"),
+        }
+    }
+}
 
-    // NOTE: use non-duplicate, sorted day values.
-    all_days()
-        .filter(|day| days_to_run.contains(day))
-        .for_each(|day| {
-            if need_space {
-                println!();
+/// The outcome of running a single puzzle.
+enum DayRun {
+    /// The puzzle was not run, with the reason why.
+    Skipped(&'static str),
+    Ran(Box<PuzzleRun>),
+}
+
+impl DayRun {
+    fn failed(error: String) -> Self {
+        DayRun::Ran(Box::new(PuzzleRun::failed(error)))
+    }
+}
+
+/// The results of a puzzle that was run.
+#[derive(Default)]
+struct PuzzleRun {
+    results: Vec<PartResult>,
+    /// Not present if the solution failed before running its parts.
+    timing: Option<Timing>,
+    wrong_parts: Vec<u8>,
+    failed_parts: Vec<(u8, String)>,
+    /// Parts that did not return an answer.
+    unsolved_parts: Vec<u8>,
+    exceeded: Option<LimitExceeded>,
+    /// Error of the solution outside of its parts, e.g. a non-zero exit code.
+    error: Option<String>,
+}
+
+impl PuzzleRun {
+    fn failed(error: String) -> Self {
+        PuzzleRun {
+            error: Some(error),
+            ..PuzzleRun::default()
+        }
+    }
+
+    fn status(&self) -> DayStatus {
+        if self.error.is_some()
+            || self.exceeded.is_some()
+            || !self.failed_parts.is_empty()
+            || !self.wrong_parts.is_empty()
+        {
+            DayStatus::Failed
+        } else if !self.unsolved_parts.is_empty() {
+            DayStatus::Unsolved
+        } else {
+            DayStatus::Solved
+        }
+    }
+}
+
+/// Runs each puzzle in a separate solution binary, running up to `jobs` binaries at the same time.
+pub fn run_multi(
+    puzzles_to_run: &HashSet<PuzzleId>,
+    is_release: bool,
+    is_timed: bool,
+    jobs: usize,
+    limits: &Limits,
+) -> MultiRun {
+    if jobs > 1 {
+        // build all binaries upfront, so parallel builds of single days do not wait on each other.
+        if let Err(e) = child_commands::build_solutions(is_release) {
+            eprintln!("Failed to build solutions: {e:?}");
+        }
+    }
+
+    run_each(
+        puzzles_to_run,
+        is_timed,
+        jobs,
+        limits,
+        |puzzle, is_buffered| {
+            if !Path::new(&paths::bin(puzzle)).exists() {
+                return (DayRun::Skipped("no solution"), Output::default());
             }
-            need_space = true;
 
-            println!("{ANSI_BOLD}Day {day}{ANSI_RESET}");
-            println!("------");
+            if !has_input(puzzle) {
+                return (DayRun::Skipped("no input"), Output::default());
+            }
+
+            run_binary(puzzle, is_timed, is_release, is_buffered, limits)
+        },
+    )
+}
 
-            let output = child_commands::run_solution(day, is_timed, is_release).unwrap();
+/// Runs each puzzle in the current process via the registered solutions.
+/// If `jobs` is larger than one or limits are set, each puzzle is run in a copy of the current process instead, so
+/// its output can be buffered and printed in day order and it can be stopped.
+pub fn run_multi_in_process(
+    solutions: &[&Solution],
+    puzzles_to_run: &HashSet<PuzzleId>,
+    is_timed: bool,
+    jobs: usize,
+    limits: &Limits,
+) -> MultiRun {
+    run_each(
+        puzzles_to_run,
+        is_timed,
+        jobs,
+        limits,
+        |puzzle, is_buffered| {
+            if !solutions.iter().any(|s| s.puzzle == puzzle) {
+                return (DayRun::Skipped("no solution"), Output::default());
+            }
 
-            if output.is_empty() {
-                println!("Not solved.");
-            } else {
-                let val = child_commands::parse_exec_time(&output, day);
-                timings.push(val);
+            if !has_input(puzzle) {
+                return (DayRun::Skipped("no input"), Output::default());
+            }
+
+            if is_buffered || limits.is_set() {
+                return match child_commands::self_command(puzzle, is_timed) {
+                    Ok(mut cmd) => run_child(puzzle, &mut cmd, is_buffered, limits),
+                    Err(e) => (
+                        DayRun::failed(format!("could not run: {e:?}")),
+                        Output::default(),
+                    ),
+                };
+            }
+
+            let run = match run_solution_in_process(solutions, puzzle, is_timed) {
+                Some(Ok(results)) => puzzle_run_from_results(puzzle, &results),
+                Some(Err(e)) => PuzzleRun {
+                    timing: None,
+                    ..puzzle_run_from_results(puzzle, &[parse_failure(e)])
+                },
+                None => PuzzleRun::failed("could not read the input".into()),
+            };
+
+            (DayRun::Ran(Box::new(run)), Output::default())
+        },
+    )
+}
+
+/// Result of a parse step that panicked. Solution binaries do not report it, as the panic ends the process.
+fn parse_failure(error: String) -> PartResult {
+    PartResult {
+        part: PARSE_STEP,
+        answer: None,
+        error: Some(error),
+        duration: Duration::ZERO,
+        samples: 0,
+        stats: None,
+        alloc: None,
+        check: None,
+    }
+}
+
+fn has_input(puzzle: PuzzleId) -> bool {
+    Path::new(&paths::input(puzzle)).exists()
+}
+
+/// Builds a solution binary and runs it. The solution is built first, so the limits only apply to the solution and
+/// not to cargo.
+fn run_binary(
+    puzzle: PuzzleId,
+    is_timed: bool,
+    is_release: bool,
+    is_buffered: bool,
+    limits: &Limits,
+) -> (DayRun, Output) {
+    let build_output = match child_commands::build_solution(puzzle, is_release, is_buffered) {
+        Ok((status, output)) if status.success() => output,
+        Ok((_, output)) => return (DayRun::failed("could not be built".into()), output),
+        Err(e) => {
+            return (
+                DayRun::failed(format!("could not build: {e:?}")),
+                Output::default(),
+            )
+        }
+    };
+
+    let (day_run, mut output) =
+        match child_commands::solution_binary_command(puzzle, is_timed, is_release) {
+            Ok(mut cmd) => run_child(puzzle, &mut cmd, is_buffered, limits),
+            Err(e) => (
+                DayRun::failed(format!("could not run: {e:?}")),
+                Output::default(),
+            ),
+        };
+
+    output.stderr.splice(0..0, build_output.stderr);
+    (day_run, output)
+}
+
+/// Runs a solution command and converts its results.
+fn run_child(
+    puzzle: PuzzleId,
+    cmd: &mut Command,
+    is_buffered: bool,
+    limits: &Limits,
+) -> (DayRun, Output) {
+    match child_commands::run_command(cmd, is_buffered, limits) {
+        Ok(run) => puzzle_run_from_child(puzzle, run),
+        Err(e) => (
+            DayRun::failed(format!("could not run: {e:?}")),
+            Output::default(),
+        ),
+    }
+}
+
+/// Runs registered solutions in a process that was invoked with `--aoc-report`. Like in a solution binary, every part
+/// prints a report record as soon as it finishes. Puzzles are run in day order.
+pub fn report_in_process(solutions: &[&Solution], puzzles: &HashSet<PuzzleId>, is_timed: bool) {
+    let mut puzzles: Vec<PuzzleId> = puzzles.iter().copied().collect();
+    puzzles.sort_unstable();
+
+    for puzzle in puzzles {
+        run_solution_in_process(solutions, puzzle, is_timed);
+    }
+}
+
+/// Runs a registered solution. Returns `None` if the puzzle has no solution or its input can not be read, and an error
+/// if the solution panicked outside of its parts.
+fn run_solution_in_process(
+    solutions: &[&Solution],
+    puzzle: PuzzleId,
+    is_timed: bool,
+) -> Option<Result<Vec<PartResult>, String>> {
+    let solution = solutions.iter().find(|s| s.puzzle == puzzle)?;
+
+    let input = match fs::read_to_string(paths::input(puzzle)) {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("Could not open input file: {e}");
+            return None;
+        }
+    };
+
+    // parts catch their own panics, so a panic here was raised by the parse step.
+    Some(
+        panic::catch_unwind(AssertUnwindSafe(|| (solution.run)(&input, is_timed)))
+            .map_err(|payload| format!("panicked: {}", panic_message(&*payload))),
+    )
+}
+
+/// Converts the results of a child process. A day fails if the process exceeded a limit, exited with an error or did
+/// not report any results.
+fn puzzle_run_from_child(puzzle: PuzzleId, run: ChildRun) -> (DayRun, Output) {
+    let mut puzzle_run = puzzle_run_from_results(puzzle, &run.results);
+    puzzle_run.exceeded = run.exceeded;
+
+    if run.results.is_empty() && run.exceeded.is_none() {
+        puzzle_run.timing = None;
+    }
+
+    if let Some(timing) = &mut puzzle_run.timing {
+        timing.limit = run.exceeded;
+    }
+
+    if run.exceeded.is_none() {
+        puzzle_run.error = if !run.status.success() {
+            Some(exit_error(run.status))
+        } else if run.results.is_empty() {
+            Some("did not report any results".into())
+        } else {
+            None
+        };
+    }
+
+    (DayRun::Ran(Box::new(puzzle_run)), run.output)
+}
+
+fn exit_error(status: ExitStatus) -> String {
+    match status.code() {
+        Some(code) => format!("exited with code {code}"),
+        None => format!("was terminated ({status})"),
+    }
+}
+
+fn puzzle_run_from_results(puzzle: PuzzleId, results: &[PartResult]) -> PuzzleRun {
+    PuzzleRun {
+        timing: Some(timing_from_results(puzzle, results)),
+        wrong_parts: results
+            .iter()
+            .filter(|r| r.check == Some(false))
+            .map(|r| r.part)
+            .collect(),
+        failed_parts: results
+            .iter()
+            .filter_map(|r| Some((r.part, r.error.clone()?)))
+            .collect(),
+        unsolved_parts: results
+            .iter()
+            .filter(|r| r.part != PARSE_STEP && r.answer.is_none() && r.error.is_none())
+            .map(|r| r.part)
+            .collect(),
+        exceeded: None,
+        error: None,
+        results: results.to_vec(),
+    }
+}
+
+/// Converts part results to a timing. Only benched parts are timed.
+fn timing_from_results(puzzle: PuzzleId, results: &[PartResult]) -> Timing {
+    let mut timing = Timing {
+        puzzle,
+        parse: None,
+        part_1: None,
+        part_2: None,
+        limit: None,
+    };
+
+    for result in results
+        .iter()
+        .filter(|r| r.part == PARSE_STEP || r.answer.is_some())
+    {
+        let Some(stats) = result.stats else {
+            continue;
+        };
+
+        let part_timing = Some(PartTiming {
+            duration: result.duration,
+            samples: result.samples,
+            stats: Some(stats),
+            alloc: result.alloc,
+        });
+
+        match result.part {
+            PARSE_STEP => timing.parse = part_timing,
+            1 => timing.part_1 = part_timing,
+            2 => timing.part_2 = part_timing,
+            _ => {}
+        }
+    }
+
+    timing
+}
+
+/// Runs each puzzle and prints its output in day order, followed by a summary.
+/// `run` is called with `is_buffered` set if several puzzles run at the same time, in which case it returns the
+/// puzzle's output instead of printing it.
+fn run_each(
+    puzzles_to_run: &HashSet<PuzzleId>,
+    is_timed: bool,
+    jobs: usize,
+    limits: &Limits,
+    run: impl Fn(PuzzleId, bool) -> (DayRun, Output) + Sync,
+) -> MultiRun {
+    let mut timings: Vec<Timing> = Vec::with_capacity(puzzles_to_run.len());
+    let mut wrong_answers: Vec<(PuzzleId, u8)> = vec![];
+    let mut failures: Vec<(PuzzleId, u8, String)> = vec![];
+    let mut failed_days: Vec<(PuzzleId, String)> = vec![];
+    let mut days: Vec<DayResult> = Vec::with_capacity(puzzles_to_run.len());
+
+    // NOTE: use non-duplicate, sorted puzzle values.
+    let mut puzzles: Vec<PuzzleId> = puzzles_to_run.iter().copied().collect();
+    puzzles.sort_unstable();
+
+    let print_header = |index: usize| {
+        if index > 0 {
+            println!();
+        }
+
+        let puzzle = puzzles[index];
+        println!("{ANSI_BOLD}{} Day {}{ANSI_RESET}", puzzle.year, puzzle.day);
+        println!("-----------");
+    };
+
+    let mut record = |index: usize, day_run: DayRun| {
+        let puzzle = puzzles[index];
+
+        let day = match day_run {
+            DayRun::Ran(puzzle_run) => {
+                let status = puzzle_run.status();
+                timings.extend(puzzle_run.timing);
+
+                for part in puzzle_run.wrong_parts {
+                    wrong_answers.push((puzzle, part));
+                }
+
+                for (part, error) in puzzle_run.failed_parts {
+                    failures.push((puzzle, part, error));
+                }
+
+                let message = match (puzzle_run.exceeded, puzzle_run.error) {
+                    (Some(exceeded), _) => {
+                        let message = limits.describe(exceeded);
+                        println!("{ANSI_BOLD}Stopped:{ANSI_RESET} {message}.");
+                        Some(message)
+                    }
+                    (None, Some(error)) => {
+                        println!("{ANSI_BOLD}Failed:{ANSI_RESET} {error}.");
+                        Some(error)
+                    }
+                    (None, None) => None,
+                };
+
+                if let Some(message) = &message {
+                    failed_days.push((puzzle, message.clone()));
+                }
+
+                DayResult {
+                    puzzle,
+                    status,
+                    parts: puzzle_run.results,
+                    message,
+                }
+            }
+            DayRun::Skipped(reason) => {
+                println!("Skipped: {reason}.");
+                DayResult {
+                    puzzle,
+                    status: DayStatus::Skipped,
+                    parts: vec![],
+                    message: Some(reason.into()),
+                }
+            }
+        };
+
+        days.push(day);
+    };
+
+    if jobs <= 1 {
+        for (index, puzzle) in puzzles.iter().enumerate() {
+            print_header(index);
+            let (day_run, _) = run(*puzzle, false);
+            record(index, day_run);
+        }
+    } else {
+        let next = AtomicUsize::new(0);
+        let (sender, receiver) = mpsc::channel();
+
+        thread::scope(|scope| {
+            for _ in 0..jobs.min(puzzles.len()) {
+                let sender = sender.clone();
+                let (next, puzzles, run) = (&next, &puzzles, &run);
+
+                scope.spawn(move || loop {
+                    let index = next.fetch_add(1, Ordering::SeqCst);
+                    let Some(puzzle) = puzzles.get(index) else {
+                        break;
+                    };
+                    if sender.send((index, run(*puzzle, true))).is_err() {
+                        break;
+                    }
+                });
+            }
+
+            drop(sender);
+
+            // print finished puzzles as soon as all puzzles before them have been printed.
+            let mut finished: Vec<Option<(DayRun, Output)>> =
+                puzzles.iter().map(|_| None).collect();
+            let mut printed = 0;
+
+            for (index, day) in receiver {
+                finished[index] = Some(day);
+
+                while let Some((day_run, output)) = finished.get_mut(printed).and_then(Option::take)
+                {
+                    print_header(printed);
+                    output.print();
+                    record(printed, day_run);
+                    printed += 1;
+                }
             }
         });
+    }
 
-    if is_timed {
+    if !wrong_answers.is_empty() {
+        let parts: Vec<String> = wrong_answers
+            .iter()
+            .map(|(puzzle, part)| format!("{puzzle} part {part}"))
+            .collect();
+        println!(
+            "\n{ANSI_BOLD}Wrong answers:{ANSI_RESET} {}",
+            parts.join(", ")
+        );
+    }
+
+    if !failures.is_empty() {
+        println!("\n{ANSI_BOLD}Failed parts:{ANSI_RESET}");
+        for (puzzle, part, error) in &failures {
+            println!("  {puzzle} {}: {error}", step_name(*part));
+        }
+    }
+
+    if !failed_days.is_empty() {
+        println!("\n{ANSI_BOLD}Failed days:{ANSI_RESET}");
+        for (puzzle, error) in &failed_days {
+            println!("  {puzzle}: {error}");
+        }
+    }
+
+    print_summary(&days);
+
+    let timings = if is_timed {
         let timings = Timings { data: timings };
         let total_millis = timings.total_millis();
         println!(
             "\n{ANSI_BOLD}Total (Run):{ANSI_RESET} {ANSI_ITALIC}{total_millis:.2}ms{ANSI_RESET}"
         );
+
+        let noisy: Vec<String> = timings
+            .noisy_parts()
+            .iter()
+            .map(|(puzzle, part, stats)| {
+                format!(
+                    "{puzzle} part {part} (±{:.0}%, {} outliers)",
+                    stats.relative_std_dev() * 100.0,
+                    stats.outliers
+                )
+            })
+            .collect();
+        if !noisy.is_empty() {
+            println!("{ANSI_BOLD}Noisy timings:{ANSI_RESET} {}", noisy.join(", "));
+        }
+
         Some(timings)
     } else {
         None
+    };
+
+    MultiRun { timings, days }
+}
+
+/// Prints the days of each status, e.g. `Solved (2): 01, 02`.
+fn print_summary(days: &[DayResult]) {
+    println!("\n{ANSI_BOLD}Summary{ANSI_RESET}");
+
+    for status in [
+        DayStatus::Solved,
+        DayStatus::Unsolved,
+        DayStatus::Failed,
+        DayStatus::Skipped,
+    ] {
+        let puzzles: Vec<String> = days
+            .iter()
+            .filter(|day| day.status == status)
+            .map(|day| day.puzzle.to_string())
+            .collect();
+
+        if !puzzles.is_empty() {
+            println!("{status} ({}): {}", puzzles.len(), puzzles.join(", "));
+        }
+    }
+}
+
+/// Name of a part in messages, e.g. `part 1` or `parse`.
+pub(crate) fn step_name(part: u8) -> String {
+    if part == PARSE_STEP {
+        "parse".into()
+    } else {
+        format!("part {part}")
     }
 }
 
@@ -59,199 +633,414 @@ impl From<std::io::Error> for Error {
     }
 }
 
-#[must_use]
-pub fn get_path_for_bin(day: Day) -> String {
-    format!("./src/bin/{day}.rs")
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::time::Duration;
+
+    use super::{puzzle_run_from_results, DayStatus, PuzzleRun};
+    use crate::{puzzle, template::runner::PartResult};
+
+    fn result(part: u8, answer: Option<&str>, error: Option<&str>) -> PartResult {
+        PartResult {
+            part,
+            answer: answer.map(ToString::to_string),
+            error: error.map(ToString::to_string),
+            duration: Duration::ZERO,
+            samples: 1,
+            stats: None,
+            alloc: None,
+            check: None,
+        }
+    }
+
+    #[test]
+    fn classifies_days() {
+        let puzzle = puzzle!(2024, 1);
+        let status = |results: &[PartResult]| puzzle_run_from_results(puzzle, results).status();
+
+        assert_eq!(
+            status(&[result(1, Some("1"), None), result(2, Some("2"), None)]),
+            DayStatus::Solved
+        );
+        assert_eq!(
+            status(&[result(1, Some("1"), None), result(2, None, None)]),
+            DayStatus::Unsolved
+        );
+        assert_eq!(
+            status(&[result(1, None, Some("panicked")), result(2, None, None)]),
+            DayStatus::Failed
+        );
+
+        let mut wrong = result(1, Some("1"), None);
+        wrong.check = Some(false);
+        assert_eq!(status(&[wrong]), DayStatus::Failed);
+
+        assert_eq!(
+            PuzzleRun::failed("exited with code 101".into()).status(),
+            DayStatus::Failed
+        );
+    }
 }
 
-/// All solutions live in isolated binaries.
-/// This module encapsulates interaction with these binaries, both invoking them as well as parsing the timing output.
+/// All solutions live in isolated binaries and are collected in the `solutions` binary.
+/// This module encapsulates invoking these binaries and collecting the results they report.
 pub mod child_commands {
-    use super::{get_path_for_bin, Error};
-    use crate::template::Day;
+    use super::Error;
+    use crate::template::{
+        limits::{LimitExceeded, Limits},
+        paths, protocol,
+        runner::PartResult,
+        PuzzleId,
+    };
     use std::{
-        io::{BufRead, BufReader},
+        collections::HashSet,
+        env,
+        io::{self, BufRead, BufReader},
         path::Path,
-        process::{Command, Stdio},
+        process::{Child, Command, ExitStatus, Stdio},
         thread,
+        time::{Duration, Instant},
     };
 
-    /// Run the solution bin for a given day
-    pub fn run_solution(day: Day, is_timed: bool, is_release: bool) -> Result<Vec<String>, Error> {
-        // skip command invocation for days that have not been scaffolded yet.
-        if !Path::new(&get_path_for_bin(day)).exists() {
-            return Ok(vec![]);
+    /// Interval in which a child with a timeout is checked for completion.
+    const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+    /// Output of a child process that was buffered instead of forwarded.
+    #[derive(Debug, Default)]
+    pub struct Output {
+        pub stdout: Vec<String>,
+        pub stderr: Vec<String>,
+    }
+
+    impl Output {
+        pub fn print(&self) {
+            for line in &self.stderr {
+                eprintln!("{line}");
+            }
+            for line in &self.stdout {
+                println!("{line}");
+            }
+        }
+    }
+
+    /// Results and output of a solution command.
+    #[derive(Debug, Default)]
+    pub struct ChildRun {
+        pub results: Vec<PartResult>,
+        pub output: Output,
+        /// The limit that stopped the command.
+        pub exceeded: Option<LimitExceeded>,
+        pub status: ExitStatus,
+    }
+
+    /// Create a `cargo` command. Removes the package variables that cargo sets for the running binary: build scripts
+    /// of dependencies watch some of them, so passing them on would rebuild these dependencies on every invocation.
+    pub fn cargo() -> Command {
+        let mut cmd = Command::new("cargo");
+
+        for (key, _) in env::vars() {
+            let is_package_var = [
+                "CARGO_PKG_",
+                "CARGO_MANIFEST_",
+                "CARGO_CRATE_",
+                "CARGO_BIN_",
+            ]
+            .iter()
+            .any(|prefix| key.starts_with(prefix));
+
+            if is_package_var || key == "CARGO_PRIMARY_PACKAGE" || key == "OUT_DIR" {
+                cmd.env_remove(key);
+            }
+        }
+
+        cmd
+    }
+
+    /// Run the `solutions` binary for a set of puzzles, which runs all of them in one process.
+    pub fn run_solutions(
+        puzzles: &HashSet<PuzzleId>,
+        is_release: bool,
+        extra_args: &[&str],
+    ) -> Result<ExitStatus, Error> {
+        let mut args: Vec<String> = vec!["run".into(), "--quiet".into()];
+
+        if is_release {
+            args.push("--release".into());
+        }
+
+        args.extend(feature_args().iter().map(ToString::to_string));
+        args.extend(["--bin".into(), "solutions".into(), "--".into()]);
+        args.extend(extra_args.iter().map(ToString::to_string));
+        args.extend(puzzles.iter().map(ToString::to_string));
+
+        let status = cargo()
+            .args(&args)
+            .stdout(Stdio::inherit())
+            .stderr(Stdio::inherit())
+            .status()?;
+
+        Ok(status)
+    }
+
+    /// Cargo arguments that enable the features of the current binary that change how solutions are measured.
+    pub fn feature_args() -> &'static [&'static str] {
+        if cfg!(feature = "alloc-stats") {
+            &["--features", "alloc-stats"]
+        } else {
+            &[]
+        }
+    }
+
+    /// Build all solution binaries.
+    pub fn build_solutions(is_release: bool) -> Result<ExitStatus, Error> {
+        let mut args = vec!["build", "--quiet", "--bins"];
+
+        if is_release {
+            args.push("--release");
         }
 
-        let day_padded = day.to_string();
-        let mut args = vec!["run", "--quiet", "--bin", &day_padded];
+        args.extend(feature_args());
+
+        Ok(cargo().args(&args).status()?)
+    }
+
+    /// Build the solution bin of a puzzle. Its compiler output is forwarded to stderr, or returned if `is_buffered` is
+    /// set.
+    pub fn build_solution(
+        puzzle: PuzzleId,
+        is_release: bool,
+        is_buffered: bool,
+    ) -> Result<(ExitStatus, Output), Error> {
+        let bin_name = puzzle.to_string();
+        let mut args = vec!["build", "--quiet", "--bin", &bin_name];
 
         if is_release {
             args.push("--release");
         }
 
+        args.extend(feature_args());
+
+        let mut cmd = cargo();
+        cmd.args(&args);
+
+        if !is_buffered {
+            return Ok((cmd.status()?, Output::default()));
+        }
+
+        let output = cmd.output()?;
+        let stderr = String::from_utf8_lossy(&output.stderr)
+            .lines()
+            .map(ToString::to_string)
+            .collect();
+
+        Ok((
+            output.status,
+            Output {
+                stdout: vec![],
+                stderr,
+            },
+        ))
+    }
+
+    /// Command that runs the built solution binary of a puzzle directly, without `cargo run`.
+    /// Solution binaries are built into the same target directory as the current binary.
+    pub fn solution_binary_command(
+        puzzle: PuzzleId,
+        is_timed: bool,
+        is_release: bool,
+    ) -> Result<Command, Error> {
+        let exe = env::current_exe()?;
+        let target_dir = exe
+            .parent()
+            .and_then(Path::parent)
+            .ok_or_else(|| io::Error::other("could not find the target directory."))?;
+
+        let profile = if is_release { "release" } else { "debug" };
+        let bin_name = format!("{puzzle}{}", env::consts::EXE_SUFFIX);
+
+        let mut cmd = Command::new(target_dir.join(profile).join(bin_name));
+        cmd.arg(protocol::REPORT_FLAG);
+
         if is_timed {
-            // mirror `--time` flag to child invocations.
-            args.push("--");
-            args.push("--time");
+            cmd.arg("--time");
         }
 
-        // spawn child command with piped stdout/stderr.
-        // forward output to stdout/stderr while grabbing stdout lines.
+        Ok(cmd)
+    }
 
-        let mut cmd = Command::new("cargo")
-            .args(&args)
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn()?;
+    /// Command that runs the solution bin for a given puzzle with `cargo run`, which rebuilds it if needed.
+    /// Returns `None` for days that have not been scaffolded yet.
+    pub fn solution_command(puzzle: PuzzleId, is_timed: bool, is_release: bool) -> Option<Command> {
+        if !Path::new(&paths::bin(puzzle)).exists() {
+            return None;
+        }
 
-        let stdout = BufReader::new(cmd.stdout.take().ok_or(super::Error::BrokenPipe)?);
-        let stderr = BufReader::new(cmd.stderr.take().ok_or(super::Error::BrokenPipe)?);
+        let bin_name = puzzle.to_string();
+        let mut args = vec!["run", "--quiet", "--bin", &bin_name];
 
-        let mut output = vec![];
+        if is_release {
+            args.push("--release");
+        }
 
-        let thread = thread::spawn(move || {
-            stderr.lines().for_each(|line| {
-                eprintln!("{}", line.unwrap());
-            });
-        });
+        args.extend(feature_args());
+        args.push("--");
+        args.push(protocol::REPORT_FLAG);
 
-        for line in stdout.lines() {
-            let line = line.unwrap();
-            println!("{line}");
-            output.push(line);
+        if is_timed {
+            // mirror `--time` flag to child invocations.
+            args.push("--time");
         }
 
-        thread.join().unwrap();
-        cmd.wait()?;
+        let mut cmd = cargo();
+        cmd.args(&args);
+        Some(cmd)
+    }
+
+    /// Command that runs a single puzzle in a copy of the current `solutions` binary.
+    pub fn self_command(puzzle: PuzzleId, is_timed: bool) -> Result<Command, Error> {
+        let mut cmd = Command::new(env::current_exe()?);
+        cmd.arg(protocol::REPORT_FLAG);
 
-        Ok(output)
+        if is_timed {
+            cmd.arg("--time");
+        }
+
+        cmd.arg(puzzle.to_string());
+        Ok(cmd)
     }
 
-    pub fn parse_exec_time(output: &[String], day: Day) -> super::Timing {
-        let mut timings = super::Timing {
-            day,
-            part_1: None,
-            part_2: None,
-            total_nanos: 0_f64,
-        };
+    /// Run a solution command within limits and collect the results it reports.
+    /// Its output is forwarded to stdout/stderr, or returned if `is_buffered` is set.
+    pub fn run_command(
+        cmd: &mut Command,
+        is_buffered: bool,
+        limits: &Limits,
+    ) -> Result<ChildRun, Error> {
+        // spawn child command with piped stdout/stderr.
+        limits.apply(cmd);
 
-        output
-            .iter()
-            .filter_map(|l| {
-                if !l.contains(" samples)") {
-                    return None;
-                }
+        let mut cmd = cmd.stdout(Stdio::piped()).stderr(Stdio::piped()).spawn()?;
 
-                let Some((timing_str, nanos)) = parse_time(l) else {
-                    eprintln!("Could not parse timings from line: {l}");
-                    return None;
-                };
+        let stdout = BufReader::new(cmd.stdout.take().ok_or(super::Error::BrokenPipe)?);
+        let stderr = BufReader::new(cmd.stderr.take().ok_or(super::Error::BrokenPipe)?);
 
-                let part = l.split(':').next()?;
-                Some((part, timing_str, nanos))
-            })
-            .for_each(|(part, timing_str, nanos)| {
-                if part.contains("Part 1") {
-                    timings.part_1 = Some(timing_str.into());
-                } else if part.contains("Part 2") {
-                    timings.part_2 = Some(timing_str.into());
+        let stderr_thread = thread::spawn(move || {
+            let mut buffer = vec![];
+            for line in stderr.lines().map_while(Result::ok) {
+                if is_buffered {
+                    buffer.push(line);
+                } else {
+                    eprintln!("{line}");
                 }
+            }
+            buffer
+        });
 
-                timings.total_nanos += nanos;
+        // stdout is read on a separate thread, so the child can be stopped while it does not print anything.
+        let stdout_thread = thread::spawn(move || {
+            let mut buffer = vec![];
+            let results = collect_results(stdout.lines().map_while(Result::ok), |line| {
+                if is_buffered {
+                    buffer.push(line);
+                } else {
+                    println!("{line}");
+                }
             });
+            (results, buffer)
+        });
 
-        timings
-    }
+        let (status, is_timed_out) = wait(&mut cmd, limits.timeout)?;
 
-    fn parse_to_float(s: &str, postfix: &str) -> Option<f64> {
-        s.split(postfix).next()?.parse().ok()
+        let (results, stdout) = stdout_thread.join().unwrap_or_default();
+        let stderr = stderr_thread.join().unwrap_or_default();
+
+        let exceeded = if is_timed_out {
+            Some(LimitExceeded::Timeout)
+        } else if limits.is_out_of_memory(status) {
+            Some(LimitExceeded::Memory)
+        } else {
+            None
+        };
+
+        Ok(ChildRun {
+            results,
+            output: Output { stdout, stderr },
+            exceeded,
+            status,
+        })
     }
 
-    fn parse_time(line: &str) -> Option<(&str, f64)> {
-        // for possible time formats, see: https://github.com/rust-lang/rust/blob/1.64.0/library/core/src/time.rs#L1176-L1200
-        let str_timing = line
-            .split(" samples)")
-            .next()?
-            .split('(')
-            .last()?
-            .split('@')
-            .next()?
-            .trim();
+    /// Waits for a child to exit and kills it once the timeout passed. Returns its exit status and whether it was
+    /// killed.
+    fn wait(child: &mut Child, timeout: Option<Duration>) -> Result<(ExitStatus, bool), Error> {
+        let Some(timeout) = timeout else {
+            return Ok((child.wait()?, false));
+        };
 
-        let parsed_timing = match str_timing {
-            s if s.contains("ns") => s.split("ns").next()?.parse::<f64>().ok(),
-            s if s.contains("µs") => parse_to_float(s, "µs").map(|x| x * 1000_f64),
-            s if s.contains("ms") => parse_to_float(s, "ms").map(|x| x * 1_000_000_f64),
-            s => parse_to_float(s, "s").map(|x| x * 1_000_000_000_f64),
-        }?;
+        let deadline = Instant::now() + timeout;
 
-        Some((str_timing, parsed_timing))
+        loop {
+            if let Some(status) = child.try_wait()? {
+                return Ok((status, false));
+            }
+            if Instant::now() >= deadline {
+                child.kill()?;
+                return Ok((child.wait()?, true));
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
     }
 
-    /// copied from: https://github.com/rust-lang/rust/blob/1.64.0/library/std/src/macros.rs#L328-L333
-    #[cfg(feature = "test_lib")]
-    macro_rules! assert_approx_eq {
-        ($a:expr, $b:expr) => {{
-            let (a, b) = (&$a, &$b);
-            assert!(
-                (*a - *b).abs() < 1.0e-6,
-                "{} is not approximately equal to {}",
-                *a,
-                *b
-            );
-        }};
+    /// Forwards the regular output of a solution binary and collects its report records.
+    fn collect_results(
+        lines: impl Iterator<Item = String>,
+        mut forward: impl FnMut(String),
+    ) -> Vec<PartResult> {
+        let mut results = vec![];
+
+        for line in lines {
+            match protocol::parse_report(&line) {
+                Some(Ok(result)) => results.push(result),
+                Some(Err(e)) => eprintln!("Could not parse report `{line}`: {e}"),
+                None => forward(line),
+            }
+        }
+
+        results
     }
 
     #[cfg(feature = "test_lib")]
     mod tests {
-        use super::parse_exec_time;
-
-        use crate::day;
+        use super::collect_results;
 
         #[test]
-        fn parses_execution_times() {
-            let res = parse_exec_time(
-                &[
-                    "Part 1: 0 (74.13ns @ 100000 samples)".into(),
-                    "Part 2: 10 (74.13ms @ 99999 samples)".into(),
-                    "".into(),
-                ],
-                day!(1),
-            );
-            assert_approx_eq!(res.total_nanos, 74130074.13_f64);
-            assert_eq!(res.part_1.unwrap(), "74.13ns");
-            assert_eq!(res.part_2.unwrap(), "74.13ms");
-        }
+        fn collects_reported_results() {
+            let lines = [
+                "Part 1: @ @ @ ( ) ms (2s @ 5 samples)",
+                r#"@aoc-report { "part": 1, "answer": "@ @ @ ( ) ms", "duration_nanos": 2000000000, "samples": 5, "status": "unchecked" }"#,
+                "Part 2: ✖             ",
+                r#"@aoc-report { "part": 2, "answer": null, "duration_nanos": 100, "samples": 1, "status": "unsolved" }"#,
+                "",
+            ];
 
-        #[test]
-        fn parses_with_patterns_in_input() {
-            let res = parse_exec_time(
-                &[
-                    "Part 1: @ @ @ ( ) ms (2s @ 5 samples)".into(),
-                    "Part 2: 10s (100ms @ 1 samples)".into(),
-                    "".into(),
-                ],
-                day!(1),
-            );
-            assert_approx_eq!(res.total_nanos, 2100000000_f64);
-            assert_eq!(res.part_1.unwrap(), "2s");
-            assert_eq!(res.part_2.unwrap(), "100ms");
+            let mut forwarded = vec![];
+            let results = collect_results(lines.iter().map(ToString::to_string), |line| {
+                forwarded.push(line);
+            });
+
+            assert_eq!(forwarded.len(), 3);
+            assert_eq!(results.len(), 2);
+            assert_eq!(results[0].answer.as_deref(), Some("@ @ @ ( ) ms"));
+            assert_eq!(results[0].duration.as_secs(), 2);
+            assert_eq!(results[0].samples, 5);
+            assert!(results[1].answer.is_none());
         }
 
         #[test]
-        fn parses_missing_parts() {
-            let res = parse_exec_time(
-                &[
-                    "Part 1: ✖        ".into(),
-                    "Part 2: ✖        ".into(),
-                    "".into(),
-                ],
-                day!(1),
-            );
-            assert_approx_eq!(res.total_nanos, 0_f64);
-            assert_eq!(res.part_1.is_none(), true);
-            assert_eq!(res.part_2.is_none(), true);
+        fn skips_malformed_reports() {
+            let lines = ["@aoc-report { \"part\": 1 }", "Part 1: 0 (74.13ns)"];
+            let results = collect_results(lines.iter().map(ToString::to_string), |_| {});
+            assert!(results.is_empty());
         }
     }
 }
@@ -1,101 +1,299 @@
 /// Encapsulates code that interacts with solution functions.
+use std::any::Any;
 use std::fmt::Display;
-use std::hint::black_box;
 use std::io::{stdout, Write};
-use std::process::Output;
+use std::panic::{self, AssertUnwindSafe};
 use std::time::{Duration, Instant};
-use std::{cmp, env, process};
+use std::{env, process};
 
+use crate::template::alloc_stats::{self, AllocStats};
+use crate::template::answers::Answers;
+use crate::template::bench::{self, BenchConfig, BenchStats};
+use crate::template::config::config;
+use crate::template::protocol;
+use crate::template::submissions::{Submission, SubmissionVerdict, Submissions};
 use crate::template::ANSI_BOLD;
-use crate::template::{aoc_cli, Day, ANSI_ITALIC, ANSI_RESET};
+use crate::template::{aoc_client, custom_input, PuzzleId, ANSI_ITALIC, ANSI_RESET};
 
-pub fn run_part<I: Copy, T: Display>(func: impl Fn(I) -> Option<T>, input: I, day: Day, part: u8) {
+/// Printed next to a result that matches the known answer.
+pub const ANSWER_CORRECT: &str = "✔";
+/// Printed next to a result that does not match the known answer.
+pub const ANSWER_WRONG: &str = "✘";
+
+/// The parse step of a solution is reported as a part with this number.
+pub const PARSE_STEP: u8 = 0;
+
+/// The outcome of running a single solution part.
+#[derive(Clone, Debug)]
+pub struct PartResult {
+    pub part: u8,
+    pub answer: Option<String>,
+    /// The error returned by the part or its panic message.
+    pub error: Option<String>,
+    /// Duration of the part. The mean of all samples if the part was benched.
+    pub duration: Duration,
+    pub samples: u128,
+    /// Benchmark statistics, only present if the part was benched.
+    pub stats: Option<BenchStats>,
+    /// Allocations of the first run, only present if the `alloc-stats` feature is enabled.
+    pub alloc: Option<AllocStats>,
+    /// Whether the answer matches the known answer, `None` if no answer is known.
+    pub check: Option<bool>,
+}
+
+/// Return types accepted for solution parts: `Option<T>` and `Result<T, E>`.
+pub trait PartOutput {
+    /// Returns the answer, `Ok(None)` if the part is not solved yet, or an error message.
+    fn answer(&self) -> Result<Option<String>, String>;
+}
+
+impl<T: Display> PartOutput for Option<T> {
+    fn answer(&self) -> Result<Option<String>, String> {
+        Ok(self.as_ref().map(ToString::to_string))
+    }
+}
+
+impl<T: Display, E: Display> PartOutput for Result<T, E> {
+    fn answer(&self) -> Result<Option<String>, String> {
+        match self {
+            Ok(answer) => Ok(Some(answer.to_string())),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+}
+
+/// Runs the parse step as part of a solution binary and returns its output.
+/// Like [`run_part`], benches the step if `--time` is passed.
+pub fn run_parse<'a, T>(func: impl Fn(&'a str) -> T, input: &'a str) -> T {
+    let is_timed = env::args().any(|x| x == "--time");
+    run_parse_with(func, input, is_timed).0
+}
+
+/// Runs the parse step, prints its duration and returns its output together with its result.
+/// Emits a report record if `--aoc-report` is passed.
+/// The parts can not run without the parse output, so a panic is printed and then resumed.
+pub fn run_parse_with<'a, T>(
+    func: impl Fn(&'a str) -> T,
+    input: &'a str,
+    is_timed: bool,
+) -> (T, PartResult) {
+    let run = run_timed(func, input, is_timed, |_| print!("Parse:"));
+
+    let (parsed, duration, stats, alloc) = match run {
+        Ok(run) => run,
+        Err(e) => {
+            print!("\r");
+            println!("Parse: ✖ {ANSI_ITALIC}panicked: {e}{ANSI_RESET}");
+            panic::resume_unwind(Box::new(e));
+        }
+    };
+
+    print!("\r");
+    println!(
+        "Parse:{}",
+        format_duration(&duration, stats.as_ref(), alloc.as_ref())
+    );
+
+    let result = PartResult {
+        part: PARSE_STEP,
+        answer: None,
+        error: None,
+        duration,
+        samples: stats.map_or(1, |stats| stats.samples),
+        stats,
+        alloc,
+        check: None,
+    };
+
+    report(&result);
+    (parsed, result)
+}
+
+/// Runs a solution part as part of a solution binary. Benches the part if `--time` is passed
+/// and submits the result if `--submit <part>` is passed.
+pub fn run_part<I: Copy, O: PartOutput>(
+    func: impl Fn(I) -> O,
+    input: I,
+    puzzle: PuzzleId,
+    part: u8,
+) {
+    let is_timed = env::args().any(|x| x == "--time");
+    let result = run_part_with(func, input, puzzle, part, is_timed);
+
+    if let Some(answer) = result.answer {
+        match submit_result(answer, puzzle, part) {
+            Some(Ok(verdict)) => println!("{verdict}"),
+            Some(Err(e)) => eprintln!("Failed to submit result: {e}"),
+            None => {}
+        }
+    }
+}
+
+/// Runs a solution part, prints its result and returns it. Emits a report record if `--aoc-report` is passed.
+/// Errors and panics are reported in the result instead of aborting the run.
+pub fn run_part_with<I: Copy, O: PartOutput>(
+    func: impl Fn(I) -> O,
+    input: I,
+    puzzle: PuzzleId,
+    part: u8,
+    is_timed: bool,
+) -> PartResult {
     let part_str = format!("Part {part}");
 
-    let (result, duration, samples) =
-        run_timed(func, input, |result| print_result(result, &part_str, ""));
+    let run = run_timed(func, input, is_timed, |output| {
+        print_result(&output.answer(), &part_str, "");
+    });
+
+    let (answer, duration, stats, alloc) = match run {
+        Ok((output, duration, stats, alloc)) => (output.answer(), duration, stats, alloc),
+        Err(e) => (Err(format!("panicked: {e}")), Duration::ZERO, None, None),
+    };
+
+    // recorded answers only apply to the puzzle input.
+    let check = answer
+        .as_ref()
+        .ok()
+        .and_then(Option::as_ref)
+        .filter(|_| custom_input().is_none())
+        .and_then(|answer| match Answers::read_from_file() {
+            Ok(answers) => answers.check(puzzle, part, answer),
+            Err(e) => {
+                eprintln!("Warning: {e}");
+                None
+            }
+        });
 
-    print_result(&result, &part_str, &format_duration(&duration, samples));
+    print_result(
+        &answer,
+        &part_str,
+        &format!(
+            "{}{}",
+            format_check(check),
+            format_duration(&duration, stats.as_ref(), alloc.as_ref())
+        ),
+    );
 
-    if let Some(result) = result {
-        submit_result(result, day, part);
+    let (answer, error) = match answer {
+        Ok(answer) => (answer, None),
+        Err(e) => (None, Some(e)),
+    };
+
+    let result = PartResult {
+        part,
+        answer,
+        error,
+        duration,
+        samples: stats.map_or(1, |stats| stats.samples),
+        stats,
+        alloc,
+        check,
+    };
+
+    report(&result);
+    result
+}
+
+/// Emits a report record as soon as a step finished, so the steps that finished before a run is stopped are
+/// still reported.
+fn report(result: &PartResult) {
+    if env::args().any(|x| x == protocol::REPORT_FLAG) {
+        println!("{}", protocol::format_report(result));
     }
 }
 
 /// Run a solution part. The behavior differs depending on whether we are running a release or debug build:
 ///  1. in debug, the function is executed once.
-///  2. in release, the function is benched (approx. 1 second of execution time or 10 samples, whatever take longer.)
+///  2. in release, the function is benched according to the [`BenchConfig`] read from the environment.
+///
+/// `hook` is called with the output of the first run.
+/// The allocations of the first run are counted if the `alloc-stats` feature is enabled.
+/// Returns the panic message if the part panics.
+#[allow(clippy::type_complexity)]
 fn run_timed<I: Copy, T>(
     func: impl Fn(I) -> T,
     input: I,
+    is_timed: bool,
     hook: impl Fn(&T),
-) -> (T, Duration, u128) {
+) -> Result<(T, Duration, Option<BenchStats>, Option<AllocStats>), String> {
     let timer = Instant::now();
-    let result = {
+    let (result, alloc) = {
         #[cfg(feature = "dhat-heap")]
         let _profiler = dhat::Profiler::new_heap();
 
-        func(input)
+        alloc_stats::measure(|| panic::catch_unwind(AssertUnwindSafe(|| func(input))))
     };
     let base_time = timer.elapsed();
 
+    let result = result.map_err(|payload| panic_message(&*payload))?;
+
     hook(&result);
 
-    let run = if std::env::args().any(|x| x == "--time") {
-        bench(func, input, &base_time)
-    } else {
-        (base_time, 1)
-    };
+    if is_timed {
+        let mut stdout = stdout();
+        print!(" > {ANSI_ITALIC}benching{ANSI_RESET}");
+        let _ = stdout.flush();
 
-    (result, run.0, run.1)
+        let stats = bench::bench(func, input, &base_time, &BenchConfig::from_env());
+        Ok((result, stats.mean, Some(stats), alloc))
+    } else {
+        Ok((result, base_time, None, alloc))
+    }
 }
 
-fn bench<I: Copy, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) -> (Duration, u128) {
-    let mut stdout = stdout();
-
-    print!(" > {ANSI_ITALIC}benching{ANSI_RESET}");
-    let _ = stdout.flush();
-
-    let bench_iterations =
-        (Duration::from_secs(1).as_nanos() / cmp::max(base_time.as_nanos(), 10)).clamp(10, 10000);
-
-    let mut timers: Vec<Duration> = vec![];
-
-    for _ in 0..bench_iterations {
-        let timer = Instant::now();
-        black_box(func(black_box(input)));
-        timers.push(timer.elapsed());
+/// Extracts the message of a panic payload.
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        (*message).to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".into()
     }
-
-    (
-        #[allow(clippy::cast_possible_truncation)]
-        Duration::from_nanos(average_duration(&timers) as u64),
-        bench_iterations,
-    )
 }
 
-fn average_duration(numbers: &[Duration]) -> u128 {
-    numbers
-        .iter()
-        .map(std::time::Duration::as_nanos)
-        .sum::<u128>()
-        / numbers.len() as u128
+fn format_duration(
+    duration: &Duration,
+    stats: Option<&BenchStats>,
+    alloc: Option<&AllocStats>,
+) -> String {
+    let memory = alloc.map_or_else(String::new, |alloc| {
+        format!(
+            ", {} peak in {} allocations",
+            alloc_stats::format_bytes(alloc.peak_bytes),
+            alloc.allocations
+        )
+    });
+
+    match stats {
+        None => format!(" ({duration:.1?}{memory})"),
+        Some(stats) => {
+            let noisy = if stats.is_noisy() {
+                format!(" {ANSI_ITALIC}noisy{ANSI_RESET}")
+            } else {
+                String::new()
+            };
+            format!(
+                " ({duration:.1?} ± {:.1?} @ {} samples{memory}){noisy}",
+                stats.std_dev, stats.samples
+            )
+        }
+    }
 }
 
-fn format_duration(duration: &Duration, samples: u128) -> String {
-    if samples == 1 {
-        format!(" ({duration:.1?})")
-    } else {
-        format!(" ({duration:.1?} @ {samples} samples)")
+fn format_check(check: Option<bool>) -> String {
+    match check {
+        Some(true) => format!(" {ANSWER_CORRECT}"),
+        Some(false) => format!(" {ANSWER_WRONG}"),
+        None => String::new(),
     }
 }
 
-fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str) {
+fn print_result(answer: &Result<Option<String>, String>, part: &str, duration_str: &str) {
     let is_intermediate_result = duration_str.is_empty();
 
-    match result {
-        Some(result) => {
-            if result.to_string().contains('\n') {
+    match answer {
+        Ok(Some(result)) => {
+            if result.contains('\n') {
                 let str = format!("{part}: ▼ {duration_str}");
                 if is_intermediate_result {
                     print!("{str}");
@@ -114,7 +312,7 @@ fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str)
                 }
             }
         }
-        None => {
+        Ok(None) => {
             if is_intermediate_result {
                 print!("{part}: ✖");
             } else {
@@ -122,17 +320,30 @@ fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str)
                 println!("{part}: ✖             ");
             }
         }
+        Err(e) => {
+            if is_intermediate_result {
+                print!("{part}: ✖");
+            } else {
+                print!("\r");
+                println!("{part}: ✖ {ANSI_ITALIC}{e}{ANSI_RESET}");
+            }
+        }
     }
 }
 
 /// Parse the arguments passed to `solve` and try to submit one part of the solution if:
 ///  1. we are in `--release` mode.
-///  2. aoc-cli is installed.
+///  2. a session cookie is configured.
+///  3. submitting is enabled in `aoc.toml`.
+///  4. the solution ran against the puzzle input.
+///  5. the answer is not known to be wrong from previous submissions, unless disabled in `aoc.toml`.
+///
+/// The verdict is appended to the submissions log.
 fn submit_result<T: Display>(
     result: T,
-    day: Day,
+    puzzle: PuzzleId,
     part: u8,
-) -> Option<Result<Output, aoc_cli::AocCommandError>> {
+) -> Option<Result<SubmissionVerdict, aoc_client::AocClientError>> {
     let args: Vec<String> = env::args().collect();
 
     if !args.contains(&"--submit".into()) {
@@ -155,11 +366,74 @@ fn submit_result<T: Display>(
         return None;
     }
 
-    if aoc_cli::check().is_err() {
-        eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
+    if !config().submit.enabled {
+        eprintln!("Refusing to submit result: submitting is disabled in `aoc.toml`.");
+        return None;
+    }
+
+    if custom_input().is_some() {
+        eprintln!("Refusing to submit result: the solution did not run against the puzzle input.");
+        return None;
+    }
+
+    if let Err(e) = aoc_client::check() {
+        eprintln!("{e}");
         process::exit(1);
     }
 
-    println!("Submitting result via aoc-cli...");
-    Some(aoc_cli::submit(day, part, &result.to_string()))
+    let answer = result.to_string();
+
+    if config().submit.check_previous {
+        let checked = Submissions::read_from_file().and_then(|s| s.check(puzzle, part, &answer));
+        if let Err(reason) = checked {
+            eprintln!("Refusing to submit result: {reason}");
+            return None;
+        }
+    }
+
+    println!("Submitting result...");
+    let verdict = aoc_client::submit(puzzle, part, &answer);
+
+    if let Ok(verdict) = &verdict {
+        let submission = Submission::new(puzzle, part, &answer, verdict.clone());
+        if let Err(e) = Submissions::record(submission) {
+            eprintln!("Failed to record submission: {e}");
+        }
+
+        if *verdict == SubmissionVerdict::Correct {
+            if let Err(e) = Answers::record(puzzle, part, &answer) {
+                eprintln!("Failed to record answer: {e}");
+            }
+        }
+    }
+
+    Some(verdict)
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{panic_message, PartOutput};
+    use std::panic;
+
+    #[test]
+    fn handles_part_outputs() {
+        assert_eq!(Some(42).answer(), Ok(Some("42".into())));
+        assert_eq!(None::<u32>.answer(), Ok(None));
+        assert_eq!(Ok::<u32, String>(7).answer(), Ok(Some("7".into())));
+        assert_eq!(
+            Err::<u32, String>("bad input".into()).answer(),
+            Err("bad input".into())
+        );
+    }
+
+    #[test]
+    fn extracts_panic_messages() {
+        let payload = panic::catch_unwind(|| panic!("static")).unwrap_err();
+        assert_eq!(panic_message(&*payload), "static");
+
+        let payload = panic::catch_unwind(|| panic!("formatted {}", 1)).unwrap_err();
+        assert_eq!(panic_message(&*payload), "formatted 1");
+    }
 }
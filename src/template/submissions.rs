@@ -0,0 +1,533 @@
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    fs,
+    io::{Error, ErrorKind},
+    str::FromStr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tinyjson::JsonValue;
+
+use crate::template::{paths, PuzzleId};
+
+static SUBMISSIONS_FILE_NAME: &str = "submissions.json";
+
+/// The verdict of the advent of code server on a submitted answer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SubmissionVerdict {
+    Correct,
+    Incorrect,
+    TooHigh,
+    TooLow,
+    /// An answer was submitted too recently, contains the time left to wait if known.
+    RateLimited(Option<Duration>),
+    /// The part was either already solved or is not unlocked yet.
+    WrongLevel,
+    Unknown(String),
+}
+
+impl SubmissionVerdict {
+    /// Interprets the message of the answer page returned by the server.
+    pub fn from_message(message: &str) -> Self {
+        if message.contains("That's the right answer") {
+            SubmissionVerdict::Correct
+        } else if message.contains("That's not the right answer") {
+            if message.contains("your answer is too high") {
+                SubmissionVerdict::TooHigh
+            } else if message.contains("your answer is too low") {
+                SubmissionVerdict::TooLow
+            } else {
+                SubmissionVerdict::Incorrect
+            }
+        } else if message.contains("You gave an answer too recently") {
+            SubmissionVerdict::RateLimited(parse_wait_time(message))
+        } else if message.contains("You don't seem to be solving the right level") {
+            SubmissionVerdict::WrongLevel
+        } else {
+            SubmissionVerdict::Unknown(message.to_string())
+        }
+    }
+
+    fn kind(&self) -> &'static str {
+        match self {
+            SubmissionVerdict::Correct => "correct",
+            SubmissionVerdict::Incorrect => "incorrect",
+            SubmissionVerdict::TooHigh => "too_high",
+            SubmissionVerdict::TooLow => "too_low",
+            SubmissionVerdict::RateLimited(_) => "rate_limited",
+            SubmissionVerdict::WrongLevel => "wrong_level",
+            SubmissionVerdict::Unknown(_) => "unknown",
+        }
+    }
+}
+
+impl Display for SubmissionVerdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SubmissionVerdict::Correct => write!(f, "That's the right answer!"),
+            SubmissionVerdict::Incorrect => write!(f, "That's not the right answer."),
+            SubmissionVerdict::TooHigh => {
+                write!(f, "That's not the right answer, your answer is too high.")
+            }
+            SubmissionVerdict::TooLow => {
+                write!(f, "That's not the right answer, your answer is too low.")
+            }
+            SubmissionVerdict::RateLimited(Some(wait)) => write!(
+                f,
+                "You gave an answer too recently, wait {}s before submitting again.",
+                wait.as_secs()
+            ),
+            SubmissionVerdict::RateLimited(None) => write!(f, "You gave an answer too recently."),
+            SubmissionVerdict::WrongLevel => write!(
+                f,
+                "You don't seem to be solving the right level. Did you already complete it?"
+            ),
+            SubmissionVerdict::Unknown(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+/// Parses wait times like "You have 1m 34s left to wait." or "Please wait one minute".
+fn parse_wait_time(message: &str) -> Option<Duration> {
+    if message.contains("one minute") {
+        return Some(Duration::from_secs(60));
+    }
+
+    let (_, rest) = message.split_once("You have ")?;
+    let (time_str, _) = rest.split_once(" left to wait")?;
+
+    let mut secs = 0;
+    for part in time_str.split_whitespace() {
+        let (value, unit) = part.split_at(part.find(|c: char| !c.is_ascii_digit())?);
+        let value: u64 = value.parse().ok()?;
+        secs += match unit {
+            "h" => value * 3600,
+            "m" => value * 60,
+            "s" => value,
+            _ => return None,
+        };
+    }
+
+    Some(Duration::from_secs(secs))
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// A submitted answer and the verdict of the server.
+#[derive(Clone, Debug)]
+pub struct Submission {
+    pub puzzle: PuzzleId,
+    pub part: u8,
+    pub answer: String,
+    pub verdict: SubmissionVerdict,
+    /// Seconds since the unix epoch.
+    pub timestamp: u64,
+}
+
+impl Submission {
+    pub fn new(puzzle: PuzzleId, part: u8, answer: &str, verdict: SubmissionVerdict) -> Self {
+        Submission {
+            puzzle,
+            part,
+            answer: answer.to_string(),
+            verdict,
+            timestamp: now(),
+        }
+    }
+}
+
+/// Represents the log of all answers submitted from this repository.
+/// Can be serialized from / to JSON.
+#[derive(Clone, Debug, Default)]
+pub struct Submissions {
+    pub data: Vec<Submission>,
+}
+
+impl Submissions {
+    /// Dehydrate submissions to a JSON file.
+    pub fn store_file(&self) -> Result<(), Error> {
+        let json = JsonValue::from(self.clone());
+        let mut file = fs::File::create(paths::shared_data_file(SUBMISSIONS_FILE_NAME))?;
+        json.format_to(&mut file)
+    }
+
+    /// Rehydrate submissions from a JSON file. If not present, returns an empty log.
+    /// A log that can not be read or parsed is an error, so it is not overwritten by accident.
+    pub fn read_from_file() -> Result<Self, String> {
+        let path = paths::shared_data_file(SUBMISSIONS_FILE_NAME);
+        match fs::read_to_string(&path) {
+            Ok(json) => {
+                Submissions::try_from(json).map_err(|e| format!("could not parse {path}: {e}"))
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Submissions::default()),
+            Err(e) => Err(format!("could not read {path}: {e}")),
+        }
+    }
+
+    /// Appends a submission to the log file.
+    pub fn record(submission: Submission) -> Result<(), String> {
+        let mut submissions = Self::read_from_file()?;
+        submissions.data.push(submission);
+        submissions.store_file().map_err(|e| e.to_string())
+    }
+
+    /// Checks if an answer can be submitted. Returns the reason if the answer is known to be
+    /// wrong, outside of a known bound or if we are still rate-limited.
+    pub fn check(&self, puzzle: PuzzleId, part: u8, answer: &str) -> Result<(), String> {
+        let submissions = self
+            .data
+            .iter()
+            .filter(|s| s.puzzle == puzzle && s.part == part);
+
+        let numeric_answer = answer.trim().parse::<i128>().ok();
+
+        for submission in submissions {
+            let previous = submission.answer.trim().parse::<i128>().ok();
+
+            match &submission.verdict {
+                SubmissionVerdict::Correct => {
+                    return Err(format!(
+                        "part {part} was already solved with answer `{}`.",
+                        submission.answer
+                    ));
+                }
+                SubmissionVerdict::Incorrect
+                | SubmissionVerdict::TooHigh
+                | SubmissionVerdict::TooLow
+                    if submission.answer.trim() == answer.trim() =>
+                {
+                    return Err(format!("answer `{answer}` is known to be wrong."));
+                }
+                SubmissionVerdict::TooHigh => {
+                    if let (Some(answer), Some(bound)) = (numeric_answer, previous) {
+                        if answer >= bound {
+                            return Err(format!(
+                                "answer `{answer}` is too high, `{bound}` was already too high."
+                            ));
+                        }
+                    }
+                }
+                SubmissionVerdict::TooLow => {
+                    if let (Some(answer), Some(bound)) = (numeric_answer, previous) {
+                        if answer <= bound {
+                            return Err(format!(
+                                "answer `{answer}` is too low, `{bound}` was already too low."
+                            ));
+                        }
+                    }
+                }
+                SubmissionVerdict::RateLimited(Some(wait)) => {
+                    let allowed_at = submission.timestamp + wait.as_secs();
+                    let now = now();
+                    if now < allowed_at {
+                        return Err(format!(
+                            "still rate-limited, wait {}s before submitting again.",
+                            allowed_at - now
+                        ));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<Submissions> for JsonValue {
+    fn from(value: Submissions) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert(
+            "data".into(),
+            JsonValue::Array(value.data.iter().map(JsonValue::from).collect()),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<String> for Submissions {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let json = JsonValue::from_str(&value).or(Err("not valid JSON file."))?;
+
+        let json_data = json
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("expected JSON document to be an object.")?
+            .get("data")
+            .ok_or("expected JSON document to have key `data`.")?
+            .get::<Vec<JsonValue>>()
+            .ok_or("expected `json.data` to be an array.")?;
+
+        Ok(Submissions {
+            data: json_data
+                .iter()
+                .map(Submission::try_from)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<&Submission> for JsonValue {
+    fn from(value: &Submission) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert("puzzle".into(), JsonValue::String(value.puzzle.to_string()));
+        map.insert("part".into(), JsonValue::Number(f64::from(value.part)));
+        map.insert("answer".into(), JsonValue::String(value.answer.clone()));
+        map.insert(
+            "verdict".into(),
+            JsonValue::String(value.verdict.kind().into()),
+        );
+        #[allow(clippy::cast_precision_loss)]
+        map.insert(
+            "timestamp".into(),
+            JsonValue::Number(value.timestamp as f64),
+        );
+
+        match &value.verdict {
+            SubmissionVerdict::RateLimited(Some(wait)) => {
+                #[allow(clippy::cast_precision_loss)]
+                map.insert("wait_secs".into(), JsonValue::Number(wait.as_secs() as f64));
+            }
+            SubmissionVerdict::Unknown(message) => {
+                map.insert("message".into(), JsonValue::String(message.clone()));
+            }
+            _ => {}
+        }
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for Submission {
+    type Error = String;
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected submission to be a JSON object.")?;
+
+        let puzzle = json
+            .get("puzzle")
+            .and_then(|v| v.get::<String>())
+            .and_then(|puzzle| PuzzleId::from_str(puzzle).ok())
+            .ok_or("Expected submission.puzzle to be a PuzzleId.")?;
+
+        let part = json
+            .get("part")
+            .and_then(|v| v.get::<f64>())
+            .map(|v| *v as u8)
+            .ok_or("Expected submission.part to be a number.")?;
+
+        let answer = json
+            .get("answer")
+            .and_then(|v| v.get::<String>())
+            .ok_or("Expected submission.answer to be a string.")?;
+
+        let timestamp = json
+            .get("timestamp")
+            .and_then(|v| v.get::<f64>())
+            .map(|v| *v as u64)
+            .ok_or("Expected submission.timestamp to be a number.")?;
+
+        let verdict = match json.get("verdict").and_then(|v| v.get::<String>()) {
+            Some(kind) => match kind.as_str() {
+                "correct" => SubmissionVerdict::Correct,
+                "incorrect" => SubmissionVerdict::Incorrect,
+                "too_high" => SubmissionVerdict::TooHigh,
+                "too_low" => SubmissionVerdict::TooLow,
+                "rate_limited" => SubmissionVerdict::RateLimited(
+                    json.get("wait_secs")
+                        .and_then(|v| v.get::<f64>())
+                        .map(|v| Duration::from_secs(*v as u64)),
+                ),
+                "wrong_level" => SubmissionVerdict::WrongLevel,
+                "unknown" => SubmissionVerdict::Unknown(
+                    json.get("message")
+                        .and_then(|v| v.get::<String>())
+                        .cloned()
+                        .unwrap_or_default(),
+                ),
+                _ => return Err("Expected submission.verdict to be a known verdict.".into()),
+            },
+            None => return Err("Expected submission.verdict to be a string.".into()),
+        };
+
+        Ok(Submission {
+            puzzle,
+            part,
+            answer: answer.clone(),
+            verdict,
+            timestamp,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::time::Duration;
+
+    use super::{Submission, SubmissionVerdict, Submissions};
+    use crate::puzzle;
+
+    fn get_mock_submissions() -> Submissions {
+        Submissions {
+            data: vec![
+                Submission::new(puzzle!(2024, 1), 1, "100", SubmissionVerdict::TooHigh),
+                Submission::new(puzzle!(2024, 1), 1, "10", SubmissionVerdict::TooLow),
+                Submission::new(puzzle!(2024, 1), 1, "50", SubmissionVerdict::Incorrect),
+                Submission::new(puzzle!(2024, 2), 1, "7", SubmissionVerdict::Correct),
+            ],
+        }
+    }
+
+    mod verdicts {
+        use std::time::Duration;
+
+        use crate::template::submissions::SubmissionVerdict;
+
+        #[test]
+        fn parses_correct_answers() {
+            let verdict = SubmissionVerdict::from_message(
+                "That's the right answer! You are one gold star closer to finding the Chief Historian.",
+            );
+            assert_eq!(verdict, SubmissionVerdict::Correct);
+        }
+
+        #[test]
+        fn parses_wrong_answers() {
+            assert_eq!(
+                SubmissionVerdict::from_message(
+                    "That's not the right answer; your answer is too high. If you're stuck, ..."
+                ),
+                SubmissionVerdict::TooHigh
+            );
+            assert_eq!(
+                SubmissionVerdict::from_message(
+                    "That's not the right answer; your answer is too low."
+                ),
+                SubmissionVerdict::TooLow
+            );
+            assert_eq!(
+                SubmissionVerdict::from_message(
+                    "That's not the right answer. If you're stuck, ..."
+                ),
+                SubmissionVerdict::Incorrect
+            );
+        }
+
+        #[test]
+        fn parses_rate_limits() {
+            assert_eq!(
+                SubmissionVerdict::from_message("You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 1m 34s left to wait. [Return to Day 1]"),
+                SubmissionVerdict::RateLimited(Some(Duration::from_secs(94)))
+            );
+            assert_eq!(
+                SubmissionVerdict::from_message(
+                    "You gave an answer too recently. You have 12s left to wait."
+                ),
+                SubmissionVerdict::RateLimited(Some(Duration::from_secs(12)))
+            );
+        }
+
+        #[test]
+        fn parses_wrong_levels() {
+            assert_eq!(
+                SubmissionVerdict::from_message(
+                    "You don't seem to be solving the right level.  Did you already complete it?"
+                ),
+                SubmissionVerdict::WrongLevel
+            );
+        }
+    }
+
+    #[test]
+    fn refuses_known_wrong_answers() {
+        let submissions = get_mock_submissions();
+        assert!(submissions.check(puzzle!(2024, 1), 1, "50").is_err());
+        assert!(submissions.check(puzzle!(2024, 1), 1, "100").is_err());
+    }
+
+    #[test]
+    fn refuses_known_wrong_text_answers() {
+        let submissions = Submissions {
+            data: vec![Submission::new(
+                puzzle!(2024, 3),
+                2,
+                "abc ",
+                SubmissionVerdict::Incorrect,
+            )],
+        };
+        assert!(submissions.check(puzzle!(2024, 3), 2, "abc").is_err());
+        assert!(submissions.check(puzzle!(2024, 3), 2, " abc\n").is_err());
+        assert!(submissions.check(puzzle!(2024, 3), 2, "abd").is_ok());
+    }
+
+    #[test]
+    fn refuses_answers_outside_bounds() {
+        let submissions = get_mock_submissions();
+        assert!(submissions.check(puzzle!(2024, 1), 1, "101").is_err());
+        assert!(submissions.check(puzzle!(2024, 1), 1, "5").is_err());
+        assert!(submissions.check(puzzle!(2024, 1), 1, "42").is_ok());
+        assert!(submissions.check(puzzle!(2024, 1), 2, "101").is_ok());
+    }
+
+    #[test]
+    fn refuses_solved_parts() {
+        let submissions = get_mock_submissions();
+        assert!(submissions.check(puzzle!(2024, 2), 1, "8").is_err());
+    }
+
+    #[test]
+    fn refuses_while_rate_limited() {
+        let submissions = Submissions {
+            data: vec![Submission::new(
+                puzzle!(2024, 1),
+                1,
+                "100",
+                SubmissionVerdict::RateLimited(Some(Duration::from_secs(60))),
+            )],
+        };
+        assert!(submissions.check(puzzle!(2024, 1), 1, "42").is_err());
+    }
+
+    #[test]
+    fn serializes_submissions() {
+        let mut submissions = get_mock_submissions();
+        submissions.data.push(Submission::new(
+            puzzle!(2024, 3),
+            2,
+            "1",
+            SubmissionVerdict::RateLimited(Some(Duration::from_secs(30))),
+        ));
+
+        let json = tinyjson::JsonValue::from(submissions.clone())
+            .stringify()
+            .unwrap();
+        let parsed = Submissions::try_from(json).unwrap();
+
+        assert_eq!(parsed.data.len(), 5);
+        assert_eq!(parsed.data[0].verdict, SubmissionVerdict::TooHigh);
+        assert_eq!(parsed.data[3].puzzle, puzzle!(2024, 2));
+        assert_eq!(
+            parsed.data[4].verdict,
+            SubmissionVerdict::RateLimited(Some(Duration::from_secs(30)))
+        );
+    }
+}
@@ -0,0 +1,528 @@
+/// Machine-readable reports of `cargo all` and `cargo time` for CI dashboards.
+///
+/// A report contains one test case per day and part, with the answer, the duration and whether the part passed.
+/// Parts pass if they returned an answer that does not contradict a known answer, and are skipped if they are not
+/// solved yet or did not run. Reports can be written as JUnit XML, TAP or JSON.
+use std::{
+    collections::HashMap, ffi::OsString, fmt::Display, fs, io, str::FromStr, time::Duration,
+};
+use tinyjson::JsonValue;
+
+use crate::template::{
+    answers::Answers,
+    protocol::PartStatus,
+    run_multi::{step_name, DayResult, DayStatus},
+    runner::{PartResult, PARSE_STEP},
+    PuzzleId,
+};
+
+/// File format of a report.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReportFormat {
+    Junit,
+    Tap,
+    Json,
+}
+
+/// A report that is written to `path` after a run.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReportTarget {
+    pub format: ReportFormat,
+    pub path: String,
+}
+
+impl ReportTarget {
+    /// Takes the `--report <format> <path>` option from raw arguments. pico-args only parses options with a single
+    /// value, so this happens before the other arguments are parsed.
+    pub fn take_from_args(args: &mut Vec<OsString>) -> Result<Option<Self>, String> {
+        let Some(index) = args.iter().position(|arg| arg == "--report") else {
+            return Ok(None);
+        };
+
+        let values: Vec<OsString> = args.drain(index..args.len().min(index + 3)).collect();
+        let [_, format, path] = values.as_slice() else {
+            return Err(
+                "`--report` expects a format and a path, e.g. `--report junit report.xml`.".into(),
+            );
+        };
+
+        Ok(Some(ReportTarget {
+            format: format.to_string_lossy().parse()?,
+            path: path.to_string_lossy().into(),
+        }))
+    }
+
+    /// Arguments that pass the report on to the `solutions` binary.
+    pub(crate) fn args(&self) -> [String; 3] {
+        [
+            "--report".into(),
+            self.format.to_string(),
+            self.path.clone(),
+        ]
+    }
+
+    /// Writes the report, printing an error if that fails. Returns whether the report was written.
+    pub(crate) fn write(&self, days: &[DayResult]) -> bool {
+        let answers = Answers::read_from_file().unwrap_or_else(|e| {
+            eprintln!("Warning: {e}");
+            Answers::default()
+        });
+        let cases = cases(days, &answers);
+
+        let report = match self.format {
+            ReportFormat::Junit => format_junit(&cases),
+            ReportFormat::Tap => format_tap(&cases),
+            ReportFormat::Json => format_json(days, &cases),
+        };
+
+        match report.and_then(|report| fs::write(&self.path, report)) {
+            Ok(()) => {
+                println!("Wrote {} report to {}.", self.format, self.path);
+                true
+            }
+            Err(e) => {
+                eprintln!("Failed to write report to {}: {e}", self.path);
+                false
+            }
+        }
+    }
+}
+
+/// Whether a test case passed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Outcome {
+    Passed,
+    Failed(String),
+    Skipped(String),
+}
+
+/// A single part of a day.
+#[derive(Clone, Debug)]
+struct Case {
+    puzzle: PuzzleId,
+    part: u8,
+    answer: Option<String>,
+    duration: Option<Duration>,
+    /// Not present if the part did not run.
+    status: Option<PartStatus>,
+    outcome: Outcome,
+}
+
+impl Case {
+    fn name(&self) -> String {
+        format!("{} {}", self.puzzle, step_name(self.part))
+    }
+}
+
+/// Converts days to test cases. Every day has a case for each part, and one for the parse step if it ran.
+fn cases(days: &[DayResult], answers: &Answers) -> Vec<Case> {
+    let mut cases = vec![];
+
+    for day in days {
+        for part in [PARSE_STEP, 1, 2] {
+            let case = match day.parts.iter().find(|result| result.part == part) {
+                Some(result) => case_from_result(day.puzzle, result, answers),
+                None if part == PARSE_STEP => continue,
+                None => Case {
+                    puzzle: day.puzzle,
+                    part,
+                    answer: None,
+                    duration: None,
+                    status: None,
+                    outcome: match (day.status, &day.message) {
+                        (DayStatus::Failed, Some(message)) => Outcome::Failed(message.clone()),
+                        (_, Some(message)) => Outcome::Skipped(message.clone()),
+                        (_, None) => Outcome::Skipped("did not run".into()),
+                    },
+                },
+            };
+
+            cases.push(case);
+        }
+    }
+
+    cases
+}
+
+fn case_from_result(puzzle: PuzzleId, result: &PartResult, answers: &Answers) -> Case {
+    let status = PartStatus::of(result);
+
+    let outcome = match status {
+        PartStatus::Correct | PartStatus::Unchecked => Outcome::Passed,
+        PartStatus::Unsolved => Outcome::Skipped("not solved yet".into()),
+        PartStatus::Failed => Outcome::Failed(result.error.clone().unwrap_or_default()),
+        PartStatus::Wrong => Outcome::Failed(match answers.get(puzzle, result.part) {
+            Some(expected) => format!("wrong answer, expected {expected}"),
+            None => "wrong answer".into(),
+        }),
+    };
+
+    Case {
+        puzzle,
+        part: result.part,
+        answer: result.answer.clone(),
+        duration: Some(result.duration),
+        status: Some(status),
+        outcome,
+    }
+}
+
+fn count(cases: &[Case], predicate: impl Fn(&Outcome) -> bool) -> usize {
+    cases.iter().filter(|case| predicate(&case.outcome)).count()
+}
+
+fn total_secs(cases: &[Case]) -> f64 {
+    cases
+        .iter()
+        .filter_map(|case| case.duration)
+        .sum::<Duration>()
+        .as_secs_f64()
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Formats the cases as JUnit XML, with one test suite per day.
+fn format_junit(cases: &[Case]) -> io::Result<String> {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+
+    xml += &format!(
+        "<testsuites name=\"advent_of_code\" tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{:.6}\">\n",
+        cases.len(),
+        count(cases, |o| matches!(o, Outcome::Failed(_))),
+        count(cases, |o| matches!(o, Outcome::Skipped(_))),
+        total_secs(cases),
+    );
+
+    for day in cases.chunk_by(|a, b| a.puzzle == b.puzzle) {
+        xml += &format!(
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{:.6}\">\n",
+            day[0].puzzle,
+            day.len(),
+            count(day, |o| matches!(o, Outcome::Failed(_))),
+            count(day, |o| matches!(o, Outcome::Skipped(_))),
+            total_secs(day),
+        );
+
+        for case in day {
+            xml += &format!(
+                "    <testcase classname=\"{}\" name=\"{}\" time=\"{:.6}\">\n",
+                case.puzzle,
+                step_name(case.part),
+                case.duration.unwrap_or_default().as_secs_f64(),
+            );
+
+            match &case.outcome {
+                Outcome::Passed => {}
+                Outcome::Failed(message) => {
+                    xml += &format!("      <failure message=\"{}\"/>\n", escape_xml(message));
+                }
+                Outcome::Skipped(message) => {
+                    xml += &format!("      <skipped message=\"{}\"/>\n", escape_xml(message));
+                }
+            }
+
+            if let Some(answer) = &case.answer {
+                xml += &format!("      <system-out>{}</system-out>\n", escape_xml(answer));
+            }
+
+            xml += "    </testcase>\n";
+        }
+
+        xml += "  </testsuite>\n";
+    }
+
+    xml += "</testsuites>\n";
+    Ok(xml)
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Formats the cases as TAP version 13. Answers and failure messages are added as YAML blocks.
+fn format_tap(cases: &[Case]) -> io::Result<String> {
+    let mut tap = format!("TAP version 13\n1..{}\n", cases.len());
+
+    for (i, case) in cases.iter().enumerate() {
+        let (result, directive) = match &case.outcome {
+            Outcome::Passed => ("ok", String::new()),
+            Outcome::Failed(_) => ("not ok", String::new()),
+            Outcome::Skipped(message) => ("ok", format!(" # SKIP {message}")),
+        };
+        tap += &format!("{result} {} - {}{directive}\n", i + 1, case.name());
+
+        let mut diagnostics = vec![];
+        if let Outcome::Failed(message) = &case.outcome {
+            diagnostics.push(("message", yaml_string(message)?));
+        }
+        if let Some(answer) = &case.answer {
+            diagnostics.push(("answer", yaml_string(answer)?));
+        }
+        if let (Some(duration), Some(_)) = (case.duration, &case.answer) {
+            diagnostics.push((
+                "duration_ms",
+                format!("{:.6}", duration.as_secs_f64() * 1000.0),
+            ));
+        }
+
+        if !diagnostics.is_empty() {
+            tap += "  ---\n";
+            for (key, value) in diagnostics {
+                tap += &format!("  {key}: {value}\n");
+            }
+            tap += "  ...\n";
+        }
+    }
+
+    Ok(tap)
+}
+
+/// Quotes a string for YAML. JSON strings are valid YAML.
+fn yaml_string(s: &str) -> io::Result<String> {
+    JsonValue::String(s.into())
+        .stringify()
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))
+}
+
+/// Formats the days and their cases as JSON.
+fn format_json(days: &[DayResult], cases: &[Case]) -> io::Result<String> {
+    let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+    map.insert(
+        "days".into(),
+        JsonValue::Array(days.iter().map(JsonValue::from).collect()),
+    );
+
+    map.insert(
+        "cases".into(),
+        JsonValue::Array(cases.iter().map(JsonValue::from).collect()),
+    );
+
+    JsonValue::Object(map)
+        .format()
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))
+}
+
+impl From<&DayResult> for JsonValue {
+    fn from(value: &DayResult) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert("puzzle".into(), JsonValue::String(value.puzzle.to_string()));
+        map.insert(
+            "status".into(),
+            JsonValue::String(value.status.to_string().to_lowercase()),
+        );
+        map.insert(
+            "message".into(),
+            value
+                .message
+                .clone()
+                .map_or(JsonValue::Null, JsonValue::String),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl From<&Case> for JsonValue {
+    fn from(value: &Case) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert("puzzle".into(), JsonValue::String(value.puzzle.to_string()));
+        map.insert("part".into(), JsonValue::Number(f64::from(value.part)));
+        map.insert(
+            "answer".into(),
+            value
+                .answer
+                .clone()
+                .map_or(JsonValue::Null, JsonValue::String),
+        );
+
+        #[allow(clippy::cast_precision_loss)]
+        map.insert(
+            "duration_nanos".into(),
+            value
+                .duration
+                .map_or(JsonValue::Null, |d| JsonValue::Number(d.as_nanos() as f64)),
+        );
+
+        map.insert(
+            "status".into(),
+            value
+                .status
+                .map_or(JsonValue::Null, |s| JsonValue::String(s.to_string())),
+        );
+
+        let (outcome, message) = match &value.outcome {
+            Outcome::Passed => ("passed", None),
+            Outcome::Failed(message) => ("failed", Some(message)),
+            Outcome::Skipped(message) => ("skipped", Some(message)),
+        };
+        map.insert("outcome".into(), JsonValue::String(outcome.into()));
+        map.insert(
+            "message".into(),
+            message.map_or(JsonValue::Null, |m| JsonValue::String(m.clone())),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl Display for ReportFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReportFormat::Junit => write!(f, "junit"),
+            ReportFormat::Tap => write!(f, "tap"),
+            ReportFormat::Json => write!(f, "json"),
+        }
+    }
+}
+
+impl FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "junit" => Ok(ReportFormat::Junit),
+            "tap" => Ok(ReportFormat::Tap),
+            "json" => Ok(ReportFormat::Json),
+            _ => Err(format!(
+                "unknown report format `{s}`. Expected `junit`, `tap` or `json`."
+            )),
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::{ffi::OsString, time::Duration};
+
+    use super::{cases, format_junit, format_tap, Outcome, ReportFormat, ReportTarget};
+    use crate::{
+        puzzle,
+        template::{
+            answers::Answers,
+            run_multi::{DayResult, DayStatus},
+            runner::PartResult,
+        },
+    };
+
+    fn result(part: u8, answer: Option<&str>, check: Option<bool>) -> PartResult {
+        PartResult {
+            part,
+            answer: answer.map(ToString::to_string),
+            error: None,
+            duration: Duration::from_millis(2),
+            samples: 1,
+            stats: None,
+            alloc: None,
+            check,
+        }
+    }
+
+    fn days() -> Vec<DayResult> {
+        vec![
+            DayResult {
+                puzzle: puzzle!(2024, 1),
+                status: DayStatus::Failed,
+                parts: vec![
+                    result(1, Some("11"), Some(true)),
+                    result(2, Some("<3"), Some(false)),
+                ],
+                message: None,
+            },
+            DayResult {
+                puzzle: puzzle!(2024, 2),
+                status: DayStatus::Failed,
+                parts: vec![result(1, None, None)],
+                message: Some("timed out after 1s".into()),
+            },
+            DayResult {
+                puzzle: puzzle!(2024, 3),
+                status: DayStatus::Skipped,
+                parts: vec![],
+                message: Some("no input".into()),
+            },
+        ]
+    }
+
+    fn answers() -> Answers {
+        let mut answers = Answers::default();
+        answers.set(puzzle!(2024, 1), 2, "12");
+        answers
+    }
+
+    #[test]
+    fn converts_days_to_cases() {
+        let outcomes: Vec<Outcome> = cases(&days(), &answers())
+            .into_iter()
+            .map(|case| case.outcome)
+            .collect();
+
+        assert_eq!(
+            outcomes,
+            [
+                Outcome::Passed,
+                Outcome::Failed("wrong answer, expected 12".into()),
+                Outcome::Skipped("not solved yet".into()),
+                Outcome::Failed("timed out after 1s".into()),
+                Outcome::Skipped("no input".into()),
+                Outcome::Skipped("no input".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn formats_junit() {
+        let xml = format_junit(&cases(&days(), &answers())).unwrap();
+
+        assert!(
+            xml.contains(r#"<testsuites name="advent_of_code" tests="6" failures="2" skipped="3""#)
+        );
+        assert!(xml.contains(r#"<testsuite name="2024-01" tests="2" failures="1" skipped="0""#));
+        assert!(xml.contains(r#"<testcase classname="2024-01" name="part 2" time="0.002000">"#));
+        assert!(xml.contains("<system-out>&lt;3</system-out>"));
+        assert!(xml.contains(r#"<skipped message="no input"/>"#));
+    }
+
+    #[test]
+    fn formats_tap() {
+        let tap = format_tap(&cases(&days(), &answers())).unwrap();
+        let lines: Vec<&str> = tap.lines().collect();
+
+        assert_eq!(
+            lines[..3],
+            ["TAP version 13", "1..6", "ok 1 - 2024-01 part 1"]
+        );
+        assert!(lines.contains(&"not ok 2 - 2024-01 part 2"));
+        assert!(lines.contains(&r#"  message: "wrong answer, expected 12""#));
+        assert!(lines.contains(&"ok 6 - 2024-03 part 2 # SKIP no input"));
+    }
+
+    #[test]
+    fn takes_report_from_args() {
+        let mut args: Vec<OsString> = ["all", "--report", "tap", "out.tap", "--jobs", "2"]
+            .iter()
+            .map(OsString::from)
+            .collect();
+
+        let report = ReportTarget::take_from_args(&mut args).unwrap();
+        assert_eq!(
+            report,
+            Some(ReportTarget {
+                format: ReportFormat::Tap,
+                path: "out.tap".into()
+            })
+        );
+        assert_eq!(args, ["all", "--jobs", "2"]);
+
+        let mut args = vec![OsString::from("--report"), OsString::from("junit")];
+        assert!(ReportTarget::take_from_args(&mut args).is_err());
+    }
+}
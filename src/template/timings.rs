@@ -1,17 +1,84 @@
-use std::{collections::HashMap, fs, io::Error, str::FromStr};
+use std::{
+    collections::HashMap,
+    fmt, fs,
+    io::{Error, ErrorKind},
+    str::FromStr,
+    time::Duration,
+};
 use tinyjson::JsonValue;
 
-use crate::template::Day;
+use crate::template::{
+    alloc_stats::AllocStats, bench::BenchStats, config::config, limits::LimitExceeded, paths,
+    runner::PARSE_STEP, Day, PuzzleId, Year,
+};
+
+static TIMINGS_FILE_NAME: &str = "timings.json";
+
+/// Version of the timings file schema. Files without a version were written before per-part durations were
+/// stored as numbers, and are migrated when read.
+pub const TIMINGS_SCHEMA_VERSION: u32 = 2;
+
+/// Benchmark time of a single part or parse step.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PartTiming {
+    /// Mean duration of a single run.
+    pub duration: Duration,
+    pub samples: u128,
+    /// Benchmark statistics, missing for timings stored before statistics were recorded.
+    pub stats: Option<BenchStats>,
+    /// Allocations of the part, only recorded if the `alloc-stats` feature is enabled.
+    pub alloc: Option<AllocStats>,
+}
+
+impl PartTiming {
+    pub fn new(duration: Duration, stats: Option<BenchStats>) -> Self {
+        PartTiming {
+            duration,
+            samples: stats.map_or(1, |stats| stats.samples),
+            stats,
+            alloc: None,
+        }
+    }
+}
 
-static TIMINGS_FILE_PATH: &str = "./data/timings.json";
+/// Formats the duration in the largest fitting unit, e.g. `74.1ns` or `1.2ms`.
+impl fmt::Display for PartTiming {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.1?}", self.duration)
+    }
+}
 
-/// Represents benchmark times for a single day.
+/// Represents benchmark times for a single puzzle.
 #[derive(Clone, Debug)]
 pub struct Timing {
-    pub day: Day,
-    pub part_1: Option<String>,
-    pub part_2: Option<String>,
-    pub total_nanos: f64,
+    pub puzzle: PuzzleId,
+    /// Timing of the parse step, only present for solutions with a parse step.
+    pub parse: Option<PartTiming>,
+    pub part_1: Option<PartTiming>,
+    pub part_2: Option<PartTiming>,
+    /// The limit that stopped the day, in which case the parts that did not finish are missing.
+    pub limit: Option<LimitExceeded>,
+}
+
+impl Timing {
+    /// Returns the timing of a part, or of the parse step for `PARSE_STEP`.
+    pub fn part(&self, part: u8) -> Option<&PartTiming> {
+        match part {
+            PARSE_STEP => self.parse.as_ref(),
+            1 => self.part_1.as_ref(),
+            2 => self.part_2.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Sum of the durations of the parse step and both parts.
+    pub fn total(&self) -> Duration {
+        [&self.parse, &self.part_1, &self.part_2]
+            .into_iter()
+            .flatten()
+            .map(|timing| timing.duration)
+            .sum()
+    }
 }
 
 /// Represents benchmark times for a set of days.
@@ -25,16 +92,19 @@ impl Timings {
     /// Dehydrate timings to a JSON file.
     pub fn store_file(&self) -> Result<(), Error> {
         let json = JsonValue::from(self.clone());
-        let mut file = fs::File::create(TIMINGS_FILE_PATH)?;
+        let mut file = fs::File::create(paths::shared_data_file(TIMINGS_FILE_NAME))?;
         json.format_to(&mut file)
     }
 
-    /// Rehydrate timings from a JSON file. If not present, returns empty timings.
-    pub fn read_from_file() -> Self {
-        fs::read_to_string(TIMINGS_FILE_PATH)
-            .map_err(|x| x.to_string())
-            .and_then(Timings::try_from)
-            .unwrap_or_default()
+    /// Rehydrate timings from a JSON file, migrating files of older schema versions.
+    /// If not present, returns empty timings. Returns an error if the file can not be read, so it is not overwritten.
+    pub fn read_from_file() -> Result<Self, String> {
+        let path = paths::shared_data_file(TIMINGS_FILE_NAME);
+        match fs::read_to_string(&path) {
+            Ok(json) => Timings::try_from(json).map_err(|e| format!("could not parse {path}: {e}")),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Timings::default()),
+            Err(e) => Err(format!("could not read {path}: {e}")),
+        }
     }
 
     /// Merge two sets of timings, overwriting `self` with `other` if present.
@@ -46,25 +116,74 @@ impl Timings {
         }
 
         for timing in &self.data {
-            if !data.iter().any(|t| t.day == timing.day) {
+            if !data.iter().any(|t| t.puzzle == timing.puzzle) {
                 data.push(timing.clone());
             }
         }
 
-        data.sort_unstable_by(|a, b| a.day.cmp(&b.day));
+        data.sort_unstable_by_key(|t| t.puzzle);
         Timings { data }
     }
 
     /// Sum up total duration of timings as millis.
     pub fn total_millis(&self) -> f64 {
-        self.data.iter().map(|x| x.total_nanos).sum::<f64>() / 1_000_000_f64
+        self.data
+            .iter()
+            .map(Timing::total)
+            .sum::<Duration>()
+            .as_secs_f64()
+            * 1000_f64
     }
 
-    pub fn is_day_complete(&self, day: Day) -> bool {
+    pub fn is_complete(&self, puzzle: PuzzleId) -> bool {
         self.data
             .iter()
-            .any(|t| t.day == day && t.part_1.is_some() && t.part_2.is_some())
+            .any(|t| t.puzzle == puzzle && t.part_1.is_some() && t.part_2.is_some())
     }
+
+    /// Returns the parts with noisy benchmark results.
+    pub fn noisy_parts(&self) -> Vec<(PuzzleId, u8, &BenchStats)> {
+        self.data
+            .iter()
+            .flat_map(|t| [(t.puzzle, 1, &t.part_1), (t.puzzle, 2, &t.part_2)])
+            .filter_map(|(puzzle, part, timing)| {
+                Some((puzzle, part, timing.as_ref()?.stats.as_ref()?))
+            })
+            .filter(|(_, _, stats)| stats.is_noisy())
+            .collect()
+    }
+
+    /// Returns the timings of a single event year.
+    pub fn for_year(&self, year: Year) -> Self {
+        Timings {
+            data: self
+                .data
+                .iter()
+                .filter(|t| t.puzzle.year == year)
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+/// Parses a duration as formatted by `Debug`, e.g. `74.13ns` or `1.2ms`. Used to migrate timings of schema
+/// version 1, which stored durations as display strings.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn parse_duration(s: &str) -> Option<Duration> {
+    let s = s.trim();
+    let unit_start = s.find(|c: char| !c.is_ascii_digit() && c != '.')?;
+    let (value, unit) = s.split_at(unit_start);
+    let value: f64 = value.parse().ok()?;
+
+    let nanos_per_unit = match unit {
+        "ns" => 1.0,
+        "µs" | "μs" | "us" => 1e3,
+        "ms" => 1e6,
+        "s" => 1e9,
+        _ => return None,
+    };
+
+    Some(Duration::from_nanos((value * nanos_per_unit).round() as u64))
 }
 
 /* -------------------------------------------------------------------------- */
@@ -73,6 +192,10 @@ impl From<Timings> for JsonValue {
     fn from(value: Timings) -> Self {
         let mut map: HashMap<String, JsonValue> = HashMap::new();
 
+        map.insert(
+            "version".into(),
+            JsonValue::Number(f64::from(TIMINGS_SCHEMA_VERSION)),
+        );
         map.insert(
             "data".into(),
             JsonValue::Array(value.data.iter().map(JsonValue::from).collect()),
@@ -85,58 +208,170 @@ impl From<Timings> for JsonValue {
 impl TryFrom<String> for Timings {
     type Error = String;
 
+    /// Timings written before timings were stored per year are migrated to the configured year.
     fn try_from(value: String) -> Result<Self, Self::Error> {
-        let json = JsonValue::from_str(&value).or(Err("not valid JSON file."))?;
+        Timings::parse(&value, config().year.or_else(Year::from_env))
+    }
+}
 
-        let json_data = json
+impl Timings {
+    /// Parses a timings file. `default_year` is used for timings of schema version 1 that do not have a year.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn parse(value: &str, default_year: Option<Year>) -> Result<Self, String> {
+        let json = JsonValue::from_str(value).or(Err("not valid JSON file."))?;
+
+        let json = json
             .get::<HashMap<String, JsonValue>>()
-            .ok_or("expected JSON document to be an object.")?
+            .ok_or("expected JSON document to be an object.")?;
+
+        // files without a version predate the versioned schema.
+        let version = match json.get("version") {
+            None => 1,
+            Some(version) => version
+                .get::<f64>()
+                .map(|version| *version as u32)
+                .ok_or("expected `json.version` to be a number.")?,
+        };
+
+        let json_data = json
             .get("data")
             .ok_or("expected JSON document to have key `data`.")?
             .get::<Vec<JsonValue>>()
             .ok_or("expected `json.data` to be an array.")?;
 
-        Ok(Timings {
-            data: json_data
+        let data = match version {
+            1 => json_data
+                .iter()
+                .map(|timing| timing_from_v1(timing, default_year))
+                .collect::<Result<_, _>>()?,
+            TIMINGS_SCHEMA_VERSION => json_data
                 .iter()
                 .map(Timing::try_from)
                 .collect::<Result<_, _>>()?,
-        })
+            _ => {
+                return Err(format!(
+                "unsupported timings version {version}, expected at most {TIMINGS_SCHEMA_VERSION}."
+            ))
+            }
+        };
+
+        Ok(Timings { data })
     }
 }
 
 /* -------------------------------------------------------------------------- */
 
-impl From<&Timing> for JsonValue {
-    fn from(value: &Timing) -> Self {
+#[allow(clippy::cast_precision_loss)]
+impl From<&PartTiming> for JsonValue {
+    fn from(value: &PartTiming) -> Self {
         let mut map: HashMap<String, JsonValue> = HashMap::new();
 
-        map.insert("day".into(), JsonValue::String(value.day.to_string()));
-        map.insert("total_nanos".into(), JsonValue::Number(value.total_nanos));
+        map.insert(
+            "nanos".into(),
+            JsonValue::Number(value.duration.as_nanos() as f64),
+        );
+        map.insert("samples".into(), JsonValue::Number(value.samples as f64));
 
-        let part_1 = value.part_1.clone().map(JsonValue::String);
-        let part_2 = value.part_2.clone().map(JsonValue::String);
+        if let Some(stats) = &value.stats {
+            map.insert("stats".into(), JsonValue::from(stats));
+        }
+
+        if let Some(alloc) = &value.alloc {
+            map.insert("alloc".into(), JsonValue::from(alloc));
+        }
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for PartTiming {
+    type Error = String;
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected part timing to be a JSON object.")?;
+
+        let nanos = json
+            .get("nanos")
+            .and_then(|v| v.get::<f64>())
+            .ok_or("Expected part timing.nanos to be a number.")?;
+
+        let samples = json
+            .get("samples")
+            .and_then(|v| v.get::<f64>())
+            .ok_or("Expected part timing.samples to be a number.")?;
+
+        let stats = json.get("stats").map(BenchStats::try_from).transpose()?;
+        let alloc = json.get("alloc").map(AllocStats::try_from).transpose()?;
+
+        Ok(PartTiming {
+            duration: Duration::from_nanos(*nanos as u64),
+            samples: *samples as u128,
+            stats,
+            alloc,
+        })
+    }
+}
+
+impl From<&Timing> for JsonValue {
+    fn from(value: &Timing) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
 
         map.insert(
-            "part_1".into(),
-            match part_1 {
-                Some(x) => x,
-                None => JsonValue::Null,
-            },
+            "year".into(),
+            JsonValue::String(value.puzzle.year.to_string()),
         );
-
         map.insert(
-            "part_2".into(),
-            match part_2 {
-                Some(x) => x,
-                None => JsonValue::Null,
-            },
+            "day".into(),
+            JsonValue::String(value.puzzle.day.to_string()),
         );
 
+        for (key, timing) in [
+            ("parse", &value.parse),
+            ("part_1", &value.part_1),
+            ("part_2", &value.part_2),
+        ] {
+            map.insert(
+                key.into(),
+                timing.as_ref().map_or(JsonValue::Null, JsonValue::from),
+            );
+        }
+
+        if let Some(limit) = value.limit {
+            map.insert("limit".into(), JsonValue::String(limit.as_str().into()));
+        }
+
         JsonValue::Object(map)
     }
 }
 
+/// Reads the puzzle of a timing, which is stored the same way in all schema versions. Timings of schema version 1
+/// that were stored before timings had a year fall back to `default_year`.
+fn read_puzzle(
+    json: &HashMap<String, JsonValue>,
+    default_year: Option<Year>,
+) -> Result<PuzzleId, String> {
+    let year = match json.get("year") {
+        Some(year) => year
+            .get::<String>()
+            .and_then(|year| Year::from_str(year).ok())
+            .ok_or("Expected timing.year to be a Year struct.")?,
+        None => default_year.ok_or(
+            "Expected timing.year to be present. Set `year` in `aoc.toml` to migrate timings without a year.",
+        )?,
+    };
+
+    let day = json
+        .get("day")
+        .and_then(|v| v.get::<String>())
+        .and_then(|day| Day::from_str(day).ok())
+        .ok_or("Expected timing.day to be a Day struct.")?;
+
+    Ok(PuzzleId::new(year, day))
+}
+
 impl TryFrom<&JsonValue> for Timing {
     type Error = String;
 
@@ -145,82 +380,189 @@ impl TryFrom<&JsonValue> for Timing {
             .get::<HashMap<String, JsonValue>>()
             .ok_or("Expected timing to be a JSON object.")?;
 
-        let day = json
-            .get("day")
-            .and_then(|v| v.get::<String>())
-            .and_then(|day| Day::from_str(day).ok())
-            .ok_or("Expected timing.day to be a Day struct.")?;
-
-        let part_1 = json
-            .get("part_1")
-            .map(|v| if v.is_null() { None } else { v.get::<String>() })
-            .ok_or("Expected timing.part_1 to be null or string.")?;
-
-        let part_2 = json
-            .get("part_2")
-            .map(|v| if v.is_null() { None } else { v.get::<String>() })
-            .ok_or("Expected timing.part_2 to be null or string.")?;
+        let part = |key: &str| {
+            json.get(key)
+                .filter(|v| !v.is_null())
+                .map(PartTiming::try_from)
+                .transpose()
+        };
 
-        let total_nanos = json
-            .get("total_nanos")
-            .and_then(|v| v.get::<f64>().copied())
-            .ok_or("Expected timing.total_nanos to be a number.")?;
+        let limit = json
+            .get("limit")
+            .filter(|v| !v.is_null())
+            .map(|v| {
+                v.get::<String>()
+                    .ok_or("Expected timing.limit to be a string.".to_string())
+                    .and_then(|limit| limit.parse())
+            })
+            .transpose()?;
 
         Ok(Timing {
-            day,
-            part_1: part_1.cloned(),
-            part_2: part_2.cloned(),
-            total_nanos,
+            puzzle: read_puzzle(json, None)?,
+            parse: part("parse")?,
+            part_1: part("part_1")?,
+            part_2: part("part_2")?,
+            limit,
         })
     }
 }
 
+/// Reads a timing of schema version 1, which stored durations as display strings next to optional statistics.
+fn timing_from_v1(value: &JsonValue, default_year: Option<Year>) -> Result<Timing, String> {
+    let json = value
+        .get::<HashMap<String, JsonValue>>()
+        .ok_or("Expected timing to be a JSON object.")?;
+
+    json.get("total_nanos")
+        .and_then(|v| v.get::<f64>())
+        .ok_or("Expected timing.total_nanos to be a number.")?;
+
+    let part = |key: &str| -> Result<Option<PartTiming>, String> {
+        let Some(duration) = json.get(key).filter(|v| !v.is_null()) else {
+            return Ok(None);
+        };
+
+        let duration = duration
+            .get::<String>()
+            .and_then(|s| parse_duration(s))
+            .ok_or(format!("Expected timing.{key} to be null or a duration."))?;
+
+        let stats = json
+            .get(&format!("{key}_stats"))
+            .map(BenchStats::try_from)
+            .transpose()?;
+
+        Ok(Some(PartTiming::new(duration, stats)))
+    };
+
+    if !json.contains_key("part_1") || !json.contains_key("part_2") {
+        return Err("Expected timing.part_1 and timing.part_2 to be null or a duration.".into());
+    }
+
+    Ok(Timing {
+        puzzle: read_puzzle(json, default_year)?,
+        parse: part("parse")?,
+        part_1: part("part_1")?,
+        part_2: part("part_2")?,
+        limit: None,
+    })
+}
+
 /* -------------------------------------------------------------------------- */
 
 #[cfg(feature = "test_lib")]
 mod tests {
-    use crate::day;
+    use std::time::Duration;
 
-    use super::{Timing, Timings};
+    use crate::puzzle;
+
+    use super::{PartTiming, Timing, Timings};
+
+    pub fn part(millis: u64) -> Option<PartTiming> {
+        Some(PartTiming::new(Duration::from_millis(millis), None))
+    }
 
     fn get_mock_timings() -> Timings {
         Timings {
             data: vec![
                 Timing {
-                    day: day!(1),
-                    part_1: Some("10ms".into()),
-                    part_2: Some("20ms".into()),
-                    total_nanos: 3e+10,
+                    puzzle: puzzle!(2024, 1),
+                    parse: None,
+                    part_1: part(10),
+                    part_2: part(20),
+                    limit: None,
                 },
                 Timing {
-                    day: day!(2),
-                    part_1: Some("30ms".into()),
-                    part_2: Some("40ms".into()),
-                    total_nanos: 7e+10,
+                    puzzle: puzzle!(2024, 2),
+                    parse: None,
+                    part_1: part(30),
+                    part_2: part(40),
+                    limit: None,
                 },
                 Timing {
-                    day: day!(4),
-                    part_1: Some("40ms".into()),
+                    puzzle: puzzle!(2024, 4),
+                    parse: None,
+                    part_1: part(40),
                     part_2: None,
-                    total_nanos: 4e+10,
+                    limit: None,
                 },
             ],
         }
     }
 
     mod deserialization {
-        use crate::{day, template::timings::Timings};
+        use std::time::Duration;
+
+        use crate::{puzzle, template::timings::Timings, year};
 
         #[test]
         fn handles_json_timings() {
-            let json = r#"{ "data": [{ "day": "01", "part_1": "1ms", "part_2": null, "total_nanos": 1000000000 }] }"#.to_string();
+            let json = r#"{ "version": 2, "data": [{ "year": "2024", "day": "01", "parse": null, "part_1": { "nanos": 1000000, "samples": 20 }, "part_2": null }] }"#.to_string();
             let timings = Timings::try_from(json).unwrap();
             assert_eq!(timings.data.len(), 1);
             let timing = timings.data.first().unwrap();
-            assert_eq!(timing.day, day!(1));
-            assert_eq!(timing.part_1, Some("1ms".to_string()));
+            assert_eq!(timing.puzzle, puzzle!(2024, 1));
+            let part_1 = timing.part_1.unwrap();
+            assert_eq!(part_1.duration, Duration::from_millis(1));
+            assert_eq!(part_1.samples, 20);
+            assert_eq!(part_1.to_string(), "1.0ms");
+            assert_eq!(timing.part_2, None);
+            assert_eq!(timing.total(), Duration::from_millis(1));
+        }
+
+        #[test]
+        fn handles_json_stats() {
+            let json = r#"{ "version": 2, "data": [{ "year": "2024", "day": "01", "part_1": { "nanos": 1000000, "samples": 10, "stats": { "samples": 10, "mean_nanos": 1000000, "median_nanos": 900000, "min_nanos": 800000, "max_nanos": 2000000, "std_dev_nanos": 300000, "outliers": 1 } }, "part_2": null }] }"#.to_string();
+            let timings = Timings::try_from(json).unwrap();
+            let timing = timings.data.first().unwrap();
+            let stats = timing.part_1.unwrap().stats.unwrap();
+            assert_eq!(stats.samples, 10);
+            assert_eq!(stats.outliers, 1);
+            assert!(timing.part_2.is_none());
+            assert_eq!(timings.noisy_parts().len(), 1);
+        }
+
+        #[test]
+        fn migrates_unversioned_timings() {
+            let json = r#"{ "data": [{ "year": "2024", "day": "01", "parse": "74.13ns", "part_1": "1.5ms", "part_2": "2.1µs", "total_nanos": 1502174, "part_1_stats": { "samples": 10, "mean_nanos": 1500000, "median_nanos": 1500000, "min_nanos": 1400000, "max_nanos": 1600000, "std_dev_nanos": 10000, "outliers": 0 } }, { "year": "2024", "day": "02", "part_1": "1s", "part_2": null, "total_nanos": 1000000000 }] }"#.to_string();
+            let timings = Timings::try_from(json).unwrap();
+
+            let timing = &timings.data[0];
+            assert_eq!(timing.parse.unwrap().duration, Duration::from_nanos(74));
+            assert_eq!(timing.part_1.unwrap().duration, Duration::from_micros(1500));
+            assert_eq!(timing.part_1.unwrap().samples, 10);
+            assert_eq!(timing.part_2.unwrap().duration, Duration::from_nanos(2100));
+            assert_eq!(timing.part_2.unwrap().samples, 1);
+
+            let timing = &timings.data[1];
+            assert_eq!(timing.part_1.unwrap().duration, Duration::from_secs(1));
             assert_eq!(timing.part_2, None);
-            assert_eq!(timing.total_nanos, 1_000_000_000_f64);
+        }
+
+        #[test]
+        fn migrates_timings_without_year() {
+            let json = r#"{"data":[{"day":"01","part_1":"10.5ms","part_2":null,"total_nanos":10500000.0},{"day":"02","part_1":"74.13ns","part_2":"1.2µs","total_nanos":1274.13}]}"#;
+
+            let timings = Timings::parse(json, Some(year!(2023))).unwrap();
+            assert_eq!(timings.data.len(), 2);
+            assert_eq!(timings.data[0].puzzle, puzzle!(2023, 1));
+            assert_eq!(
+                timings.data[0].part_1.unwrap().duration,
+                Duration::from_micros(10500)
+            );
+            assert_eq!(timings.data[1].puzzle, puzzle!(2023, 2));
+            assert_eq!(
+                timings.data[1].part_2.unwrap().duration,
+                Duration::from_nanos(1200)
+            );
+
+            assert!(Timings::parse(json, None).is_err());
+        }
+
+        #[test]
+        fn rejects_newer_versions() {
+            let json = r#"{ "version": 3, "data": [] }"#.to_string();
+            assert!(Timings::try_from(json).is_err());
         }
 
         #[test]
@@ -240,20 +582,46 @@ mod tests {
         #[test]
         #[should_panic]
         fn panics_for_malformed_timings() {
-            let json = r#"{ "data": [{ "day": "01" }, { "day": "26" }, { "day": "02", "part_2": null, "total_nanos": 0 }] }"#.to_string();
+            let json = r#"{ "data": [{ "year": "2024", "day": "01" }, { "year": "2024", "day": "26" }, { "year": "2024", "day": "02", "part_2": null, "total_nanos": 0 }] }"#.to_string();
             Timings::try_from(json).unwrap();
         }
     }
 
     mod serialization {
         use super::get_mock_timings;
+        use crate::template::{limits::LimitExceeded, timings::Timings};
         use std::collections::HashMap;
         use tinyjson::JsonValue;
 
+        #[test]
+        fn roundtrips_timings() {
+            let timings = get_mock_timings();
+            let json = JsonValue::from(timings.clone()).stringify().unwrap();
+            assert!(json.contains(r#""version":2"#));
+
+            let parsed = Timings::try_from(json).unwrap();
+            assert_eq!(parsed.data.len(), 3);
+            assert_eq!(parsed.data[1].part_2, timings.data[1].part_2);
+            assert_eq!(parsed.total_millis(), 140_f64);
+            assert_eq!(parsed.data[1].limit, None);
+        }
+
+        #[test]
+        fn roundtrips_exceeded_limits() {
+            let mut timings = get_mock_timings();
+            timings.data[2].part_2 = None;
+            timings.data[2].limit = Some(LimitExceeded::Timeout);
+
+            let json = JsonValue::from(timings).stringify().unwrap();
+            let parsed = Timings::try_from(json).unwrap();
+            assert_eq!(parsed.data[2].limit, Some(LimitExceeded::Timeout));
+            assert_eq!(parsed.data[2].part_2, None);
+        }
+
         #[test]
         fn serializes_timings() {
             let timings = get_mock_timings();
-            let value = JsonValue::try_from(timings).unwrap();
+            let value = JsonValue::from(timings);
             assert_eq!(
                 value
                     .get::<HashMap<String, JsonValue>>()
@@ -268,58 +636,63 @@ mod tests {
         }
     }
 
-    mod is_day_complete {
+    mod is_complete {
         use crate::{
-            day,
+            puzzle,
             template::timings::{Timing, Timings},
         };
 
+        use super::part;
+
         #[test]
         fn handles_completed_days() {
             let timings = Timings {
                 data: vec![Timing {
-                    day: day!(1),
-                    part_1: Some("1ms".into()),
-                    part_2: Some("2ms".into()),
-                    total_nanos: 3_000_000_000_f64,
+                    puzzle: puzzle!(2024, 1),
+                    parse: None,
+                    part_1: part(1),
+                    part_2: part(2),
+                    limit: None,
                 }],
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), true);
+            assert!(timings.is_complete(puzzle!(2024, 1)));
         }
 
         #[test]
         fn handles_partial_days() {
             let timings = Timings {
                 data: vec![Timing {
-                    day: day!(1),
-                    part_1: Some("1ms".into()),
+                    puzzle: puzzle!(2024, 1),
+                    parse: None,
+                    part_1: part(1),
                     part_2: None,
-                    total_nanos: 1_000_000_000_f64,
+                    limit: None,
                 }],
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), false);
+            assert!(!timings.is_complete(puzzle!(2024, 1)));
         }
 
         #[test]
         fn handles_uncompleted_days() {
             let timings = Timings {
                 data: vec![Timing {
-                    day: day!(1),
+                    puzzle: puzzle!(2024, 1),
+                    parse: None,
                     part_1: None,
                     part_2: None,
-                    total_nanos: 0.0,
+                    limit: None,
                 }],
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), false);
+            assert!(!timings.is_complete(puzzle!(2024, 1)));
         }
     }
 
     mod merge {
         use crate::{
-            day,
+            puzzle,
             template::timings::{Timing, Timings},
         };
 
@@ -330,18 +703,19 @@ mod tests {
             let timings = get_mock_timings();
             let other = Timings {
                 data: vec![Timing {
-                    day: day!(3),
+                    puzzle: puzzle!(2024, 3),
+                    parse: None,
                     part_1: None,
                     part_2: None,
-                    total_nanos: 0_f64,
+                    limit: None,
                 }],
             };
             let merged = timings.merge(&other);
             assert_eq!(merged.data.len(), 4);
-            assert_eq!(merged.data[0].day, day!(1));
-            assert_eq!(merged.data[1].day, day!(2));
-            assert_eq!(merged.data[2].day, day!(3));
-            assert_eq!(merged.data[3].day, day!(4));
+            assert_eq!(merged.data[0].puzzle, puzzle!(2024, 1));
+            assert_eq!(merged.data[1].puzzle, puzzle!(2024, 2));
+            assert_eq!(merged.data[2].puzzle, puzzle!(2024, 3));
+            assert_eq!(merged.data[3].puzzle, puzzle!(2024, 4));
         }
 
         #[test]
@@ -350,19 +724,20 @@ mod tests {
 
             let other = Timings {
                 data: vec![Timing {
-                    day: day!(2),
+                    puzzle: puzzle!(2024, 2),
+                    parse: None,
                     part_1: None,
                     part_2: None,
-                    total_nanos: 0_f64,
+                    limit: None,
                 }],
             };
             let merged = timings.merge(&other);
 
             assert_eq!(merged.data.len(), 3);
-            assert_eq!(merged.data[0].day, day!(1));
-            assert_eq!(merged.data[1].day, day!(2));
-            assert_eq!(merged.data[1].total_nanos, 0_f64);
-            assert_eq!(merged.data[2].day, day!(4));
+            assert_eq!(merged.data[0].puzzle, puzzle!(2024, 1));
+            assert_eq!(merged.data[1].puzzle, puzzle!(2024, 2));
+            assert_eq!(merged.data[1].part_1, None);
+            assert_eq!(merged.data[2].puzzle, puzzle!(2024, 4));
         }
 
         #[test]
@@ -0,0 +1,533 @@
+/// History of stored benchmark runs, used to detect performance regressions.
+///
+/// Every `cargo time --store` appends a run to `data/timings_history.json`, together with the time of the run,
+/// the git commit it ran on and the machine it ran on.
+use std::{
+    collections::HashMap,
+    env, fs,
+    io::{Error, ErrorKind},
+    process::Command,
+    str::FromStr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tinyjson::JsonValue;
+
+use crate::template::{
+    config::config,
+    paths, readme_benchmarks,
+    run_multi::step_name,
+    runner::PARSE_STEP,
+    timings::{Timing, Timings},
+    PuzzleId, ANSI_BOLD, ANSI_RESET,
+};
+
+static HISTORY_FILE_NAME: &str = "timings_history.json";
+
+/// The mean duration of a part (or of the parse step) in a benchmark run.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Measurement {
+    pub puzzle: PuzzleId,
+    pub part: u8,
+    pub nanos: u128,
+}
+
+/// A stored benchmark run.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HistoryEntry {
+    /// Unix timestamp of the run in seconds.
+    pub timestamp: u64,
+    /// Short hash of the git commit the run was benched on, suffixed with `-dirty` for uncommitted changes.
+    pub commit: Option<String>,
+    pub machine: String,
+    pub measurements: Vec<Measurement>,
+}
+
+impl HistoryEntry {
+    /// Creates an entry for timings that were benched on the current commit and machine.
+    pub fn new(timings: &Timings) -> Self {
+        HistoryEntry {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+            commit: git_commit(),
+            machine: machine(),
+            measurements: measurements(timings),
+        }
+    }
+}
+
+/// A part that got slower than it was in previous runs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Regression {
+    pub puzzle: PuzzleId,
+    pub part: u8,
+    pub duration: Duration,
+    pub previous: Duration,
+    pub best: Duration,
+}
+
+impl Regression {
+    /// Relative change compared to a previous duration, e.g. `0.25` if the part got 25% slower.
+    pub fn change(&self, before: Duration) -> f64 {
+        self.duration.as_secs_f64() / before.as_secs_f64().max(f64::EPSILON) - 1.0
+    }
+}
+
+/// Represents the stored benchmark runs.
+/// Can be serialized from / to JSON.
+#[derive(Clone, Debug, Default)]
+pub struct TimingsHistory {
+    pub data: Vec<HistoryEntry>,
+}
+
+impl TimingsHistory {
+    /// Dehydrate the history to a JSON file.
+    pub fn store_file(&self) -> Result<(), Error> {
+        self.store_to(&paths::shared_data_file(HISTORY_FILE_NAME))
+    }
+
+    /// Rehydrate the history from a JSON file. If not present, returns an empty history.
+    /// A history that can not be read or parsed is an error, so it is not overwritten by accident.
+    pub fn read_from_file() -> Result<Self, String> {
+        Self::read_from(&paths::shared_data_file(HISTORY_FILE_NAME))
+    }
+
+    /// Appends an entry to the history file.
+    pub fn record(entry: HistoryEntry) -> Result<(), String> {
+        Self::record_in(&paths::shared_data_file(HISTORY_FILE_NAME), entry)
+    }
+
+    fn store_to(&self, path: &str) -> Result<(), Error> {
+        let json = JsonValue::from(self.clone());
+        let mut file = fs::File::create(path)?;
+        json.format_to(&mut file)
+    }
+
+    fn read_from(path: &str) -> Result<Self, String> {
+        match fs::read_to_string(path) {
+            Ok(json) => {
+                TimingsHistory::try_from(json).map_err(|e| format!("could not parse {path}: {e}"))
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(TimingsHistory::default()),
+            Err(e) => Err(format!("could not read {path}: {e}")),
+        }
+    }
+
+    fn record_in(path: &str, entry: HistoryEntry) -> Result<(), String> {
+        let mut history = Self::read_from(path)?;
+        history.data.push(entry);
+        history.store_to(path).map_err(|e| e.to_string())
+    }
+
+    /// Number of runs recorded on a machine.
+    pub fn runs_on(&self, machine: &str) -> usize {
+        self.data.iter().filter(|e| e.machine == machine).count()
+    }
+
+    /// Compares an entry against the previous runs on the same machine. A part regressed if it is more than
+    /// `threshold` (e.g. `0.1` for 10%) slower than its best or its previous duration.
+    pub fn regressions(&self, entry: &HistoryEntry, threshold: f64) -> Vec<Regression> {
+        let previous_runs: Vec<&HistoryEntry> = self
+            .data
+            .iter()
+            .filter(|e| e.machine == entry.machine)
+            .collect();
+
+        entry
+            .measurements
+            .iter()
+            .filter_map(|current| {
+                let previous: Vec<u128> = previous_runs
+                    .iter()
+                    .filter_map(|run| {
+                        run.measurements
+                            .iter()
+                            .find(|m| m.puzzle == current.puzzle && m.part == current.part)
+                    })
+                    .map(|m| m.nanos)
+                    .collect();
+
+                let regression = Regression {
+                    puzzle: current.puzzle,
+                    part: current.part,
+                    duration: nanos(current.nanos),
+                    previous: nanos(*previous.last()?),
+                    best: nanos(*previous.iter().min()?),
+                };
+
+                // the best duration is never slower than the previous one, so this covers both.
+                (regression.change(regression.best) > threshold).then_some(regression)
+            })
+            .collect()
+    }
+}
+
+/// Compares timings against the benchmark history if a threshold (in percent) is passed, and stores them if `store`
+/// is set. Returns whether a part regressed or the history could not be read for the comparison.
+pub fn record(timings: &Timings, store: bool, compare: Option<f64>) -> bool {
+    let entry = HistoryEntry::new(timings);
+    let regressed = compare.is_some_and(|threshold| self::compare(&entry, threshold));
+
+    if store {
+        self::store(timings, entry);
+    }
+
+    regressed
+}
+
+/// Prints the parts that are more than `threshold` percent slower than in previous runs on this machine.
+fn compare(entry: &HistoryEntry, threshold: f64) -> bool {
+    let history = match TimingsHistory::read_from_file() {
+        Ok(history) => history,
+        Err(e) => {
+            eprintln!("Failed to compare against the benchmark history: {e}");
+            return true;
+        }
+    };
+    let runs = history.runs_on(&entry.machine);
+
+    println!();
+
+    if runs == 0 {
+        println!(
+            "No stored timings of \"{}\" to compare against.",
+            entry.machine
+        );
+        return false;
+    }
+
+    let regressions = history.regressions(entry, threshold / 100.0);
+
+    if regressions.is_empty() {
+        println!(
+            "No part is more than {threshold}% slower than in {runs} stored run(s) of \"{}\".",
+            entry.machine
+        );
+        return false;
+    }
+
+    println!(
+        "{ANSI_BOLD}Regressions{ANSI_RESET} (more than {threshold}% slower than in {runs} stored run(s) of \"{}\"):",
+        entry.machine
+    );
+
+    for regression in &regressions {
+        println!(
+            "{} {}: {:.1?} ({:+.1}% vs. previous {:.1?}, {:+.1}% vs. best {:.1?})",
+            regression.puzzle,
+            step_name(regression.part),
+            regression.duration,
+            regression.change(regression.previous) * 100.0,
+            regression.previous,
+            regression.change(regression.best) * 100.0,
+            regression.best,
+        );
+    }
+
+    true
+}
+
+/// Merges timings into the timings file, appends them to the benchmark history and updates the readme.
+fn store(timings: &Timings, entry: HistoryEntry) {
+    // an unreadable timings file is left alone, so stored timings are not lost.
+    let merged_timings = match Timings::read_from_file() {
+        Ok(stored_timings) => stored_timings.merge(timings),
+        Err(e) => {
+            eprintln!("Failed to store benchmarks: {e}");
+            return;
+        }
+    };
+    merged_timings.store_file().unwrap();
+
+    if let Err(e) = TimingsHistory::record(entry) {
+        eprintln!("Failed to store benchmark history: {e}");
+    }
+
+    println!();
+    match readme_benchmarks::update(merged_timings) {
+        Ok(()) => {
+            println!("Stored updated benchmarks.");
+        }
+        Err(_) => {
+            eprintln!("Failed to store updated benchmarks.");
+        }
+    }
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn nanos(value: u128) -> Duration {
+    Duration::from_nanos(value as u64)
+}
+
+/// Returns the mean durations of the benched parts.
+fn measurements(timings: &Timings) -> Vec<Measurement> {
+    let measurement = |timing: &Timing, part: u8| {
+        Some(Measurement {
+            puzzle: timing.puzzle,
+            part,
+            nanos: timing.part(part)?.duration.as_nanos(),
+        })
+    };
+
+    timings
+        .data
+        .iter()
+        .flat_map(|timing| [PARSE_STEP, 1, 2].map(|part| measurement(timing, part)))
+        .flatten()
+        .collect()
+}
+
+fn git_commit() -> Option<String> {
+    let git = |args: &[&str]| {
+        let output = Command::new("git").args(args).output().ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+
+    let commit = git(&["rev-parse", "--short", "HEAD"])?;
+    let is_dirty = git(&["status", "--porcelain", "--untracked-files=no"])
+        .is_some_and(|status| !status.is_empty());
+
+    Some(if is_dirty {
+        format!("{commit}-dirty")
+    } else {
+        commit
+    })
+}
+
+/// Identifies the current machine by the name in `aoc.toml`, falling back to the host name.
+pub fn machine() -> String {
+    if let Some(machine) = &config().history.machine {
+        return machine.clone();
+    }
+
+    env::var("HOSTNAME")
+        .or_else(|_| env::var("COMPUTERNAME"))
+        .ok()
+        .or_else(|| fs::read_to_string("/etc/hostname").ok())
+        .or_else(|| {
+            let output = Command::new("hostname").output().ok()?;
+            Some(String::from_utf8_lossy(&output.stdout).to_string())
+        })
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "unknown".into())
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<TimingsHistory> for JsonValue {
+    fn from(value: TimingsHistory) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert(
+            "data".into(),
+            JsonValue::Array(value.data.iter().map(JsonValue::from).collect()),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<String> for TimingsHistory {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let json = JsonValue::from_str(&value).or(Err("not valid JSON file."))?;
+
+        let json_data = json
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("expected JSON document to be an object.")?
+            .get("data")
+            .ok_or("expected JSON document to have key `data`.")?
+            .get::<Vec<JsonValue>>()
+            .ok_or("expected `json.data` to be an array.")?;
+
+        Ok(TimingsHistory {
+            data: json_data
+                .iter()
+                .map(HistoryEntry::try_from)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[allow(clippy::cast_precision_loss)]
+impl From<&HistoryEntry> for JsonValue {
+    fn from(value: &HistoryEntry) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert(
+            "timestamp".into(),
+            JsonValue::Number(value.timestamp as f64),
+        );
+        map.insert(
+            "commit".into(),
+            value
+                .commit
+                .clone()
+                .map_or(JsonValue::Null, JsonValue::String),
+        );
+        map.insert("machine".into(), JsonValue::String(value.machine.clone()));
+
+        let measurements = value
+            .measurements
+            .iter()
+            .map(|m| {
+                let mut map: HashMap<String, JsonValue> = HashMap::new();
+                map.insert("puzzle".into(), JsonValue::String(m.puzzle.to_string()));
+                map.insert("part".into(), JsonValue::Number(f64::from(m.part)));
+                map.insert("nanos".into(), JsonValue::Number(m.nanos as f64));
+                JsonValue::Object(map)
+            })
+            .collect();
+
+        map.insert("measurements".into(), JsonValue::Array(measurements));
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for HistoryEntry {
+    type Error = String;
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("expected history entry to be an object.")?;
+
+        let measurements = json
+            .get("measurements")
+            .and_then(|v| v.get::<Vec<JsonValue>>())
+            .ok_or("expected history entry to have an array `measurements`.")?
+            .iter()
+            .map(|m| {
+                let m = m
+                    .get::<HashMap<String, JsonValue>>()
+                    .ok_or("expected measurement to be an object.")?;
+
+                let number = |key: &str| {
+                    m.get(key)
+                        .and_then(|v| v.get::<f64>())
+                        .copied()
+                        .ok_or(format!("expected measurement.{key} to be a number."))
+                };
+
+                Ok(Measurement {
+                    puzzle: m
+                        .get("puzzle")
+                        .and_then(|v| v.get::<String>())
+                        .ok_or("expected measurement.puzzle to be a string.")?
+                        .parse()
+                        .map_err(|e| format!("invalid measurement.puzzle: {e}"))?,
+                    part: number("part")? as u8,
+                    nanos: number("nanos")? as u128,
+                })
+            })
+            .collect::<Result<_, String>>()?;
+
+        Ok(HistoryEntry {
+            timestamp: json
+                .get("timestamp")
+                .and_then(|v| v.get::<f64>())
+                .map(|x| *x as u64)
+                .ok_or("expected history entry to have a number `timestamp`.")?,
+            commit: json.get("commit").and_then(|v| v.get::<String>()).cloned(),
+            machine: json
+                .get("machine")
+                .and_then(|v| v.get::<String>())
+                .ok_or("expected history entry to have a string `machine`.")?
+                .clone(),
+            measurements,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::{env, fs, time::Duration};
+
+    use super::{HistoryEntry, Measurement, TimingsHistory};
+    use crate::puzzle;
+
+    fn entry(machine: &str, nanos: &[(u8, u128)]) -> HistoryEntry {
+        HistoryEntry {
+            timestamp: 1_700_000_000,
+            commit: Some("abc1234".into()),
+            machine: machine.into(),
+            measurements: nanos
+                .iter()
+                .map(|&(part, nanos)| Measurement {
+                    puzzle: puzzle!(2024, 1),
+                    part,
+                    nanos,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn detects_regressions() {
+        let history = TimingsHistory {
+            data: vec![
+                entry("a", &[(1, 100), (2, 100)]),
+                entry("a", &[(1, 120), (2, 100)]),
+                entry("b", &[(1, 10), (2, 10)]),
+            ],
+        };
+
+        let regressions = history.regressions(&entry("a", &[(1, 125), (2, 105), (0, 50)]), 0.1);
+
+        assert_eq!(regressions.len(), 1);
+        assert_eq!(regressions[0].part, 1);
+        assert_eq!(regressions[0].previous, Duration::from_nanos(120));
+        assert_eq!(regressions[0].best, Duration::from_nanos(100));
+        assert!((regressions[0].change(regressions[0].best) - 0.25).abs() < 1e-9);
+    }
+
+    #[test]
+    fn ignores_other_machines() {
+        let history = TimingsHistory {
+            data: vec![entry("b", &[(1, 10)])],
+        };
+
+        assert!(history
+            .regressions(&entry("a", &[(1, 100)]), 0.1)
+            .is_empty());
+        assert_eq!(history.runs_on("a"), 0);
+    }
+
+    #[test]
+    fn roundtrips_json_history() {
+        let history = TimingsHistory {
+            data: vec![entry("a", &[(0, 10), (1, 100), (2, 1000)])],
+        };
+
+        let json = tinyjson::JsonValue::from(history.clone())
+            .stringify()
+            .unwrap();
+        let parsed = TimingsHistory::try_from(json).unwrap();
+        assert_eq!(parsed.data, history.data);
+    }
+
+    #[test]
+    fn keeps_malformed_history_files() {
+        let path = env::temp_dir().join(format!("aoc-history-{}.json", std::process::id()));
+        let path = path.to_str().unwrap();
+        fs::write(path, r#"{ "version": 2, "runs": [] }"#).unwrap();
+
+        let result = TimingsHistory::record_in(path, entry("ci", &[(1, 100)]));
+        let content = fs::read_to_string(path).unwrap();
+        fs::remove_file(path).unwrap();
+
+        assert!(result.is_err());
+        assert_eq!(content, r#"{ "version": 2, "runs": [] }"#);
+    }
+}